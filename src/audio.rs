@@ -0,0 +1,472 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use eframe::glow::{self, HasContext};
+use rustfft::num_complex::Complex;
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, formats::FormatOptions, io::MediaSourceStream,
+    meta::MetadataOptions, probe::Hint,
+};
+
+use crate::{guard, renderer::Levels, AllocGuard, Error};
+
+/// A decoded audio track, held in memory as interleaved samples.
+#[derive(Debug)]
+pub struct Track {
+    samples: Vec<f32>,
+    channels: usize,
+    rate: u32,
+}
+
+impl Track {
+    pub fn new(samples: Vec<f32>, channels: usize, rate: u32) -> Self {
+        Self {
+            samples,
+            channels,
+            rate,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let stream =
+            MediaSourceStream::new(Box::new(std::fs::File::open(path)?), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+            hint.with_extension(extension);
+        }
+
+        let mut format = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )?
+            .format;
+
+        let track = format
+            .default_track()
+            .ok_or_else(|| Error::Audio("The file does not contain any audio track".into()))?;
+        let id = track.id;
+        let rate = track
+            .codec_params
+            .sample_rate
+            .ok_or_else(|| Error::Audio("The audio track has an unknown sample rate".into()))?;
+
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+
+        let mut samples = Vec::new();
+        let mut channels = 1;
+
+        loop {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(symphonia::core::errors::Error::IoError(err))
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    break
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            if packet.track_id() != id {
+                continue;
+            }
+
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(symphonia::core::errors::Error::DecodeError(err)) => {
+                    tracing::warn!("Skipping undecodable audio packet: {err}");
+
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            let spec = *decoded.spec();
+            channels = spec.channels.count();
+
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+
+            samples.extend_from_slice(buffer.samples());
+        }
+
+        Ok(Self::new(samples, channels, rate))
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels
+    }
+
+    /// The duration of the track, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / self.rate as f32
+    }
+
+    /// The sample of `channel` at `frame`, or silence when out of bounds.
+    pub fn sample(&self, frame: i64, channel: usize) -> f32 {
+        usize::try_from(frame)
+            .ok()
+            .filter(|frame| *frame < self.frames())
+            .map(|frame| self.samples[frame * self.channels + channel.min(self.channels - 1)])
+            .unwrap_or(0.0)
+    }
+
+    /// The mean of all channels at `frame`.
+    pub fn mono(&self, frame: i64) -> f32 {
+        (0..self.channels)
+            .map(|channel| self.sample(frame, channel))
+            .sum::<f32>()
+            / self.channels as f32
+    }
+}
+
+/// Plays a [`Track`] on the default output device, at a position that can be synchronized at will.
+pub struct Player {
+    track: Arc<Track>,
+    position: Arc<AtomicU64>,
+    _stream: cpal::Stream,
+}
+
+impl std::fmt::Debug for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Player")
+            .field("track", &self.track)
+            .field("position", &self.position)
+            .field("stream", &())
+            .finish()
+    }
+}
+
+impl Player {
+    /// The tolerated drift between the playback and the requested position before seeking, in seconds.
+    const DRIFT: f64 = 0.05;
+
+    pub fn new(track: Arc<Track>) -> Result<Self, Error> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| Error::Audio("No audio output device is available".into()))?;
+        let config = device
+            .default_output_config()
+            .map_err(|err| Error::Audio(err.to_string()))?;
+
+        let position = Arc::new(AtomicU64::new(0f64.to_bits()));
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::I16 => {
+                Self::stream::<i16>(&device, &config.into(), track.clone(), position.clone())
+            }
+            cpal::SampleFormat::U16 => {
+                Self::stream::<u16>(&device, &config.into(), track.clone(), position.clone())
+            }
+            _ => Self::stream::<f32>(&device, &config.into(), track.clone(), position.clone()),
+        }?;
+
+        stream.play().map_err(|err| Error::Audio(err.to_string()))?;
+
+        Ok(Self {
+            track,
+            position,
+            _stream: stream,
+        })
+    }
+
+    fn stream<T: cpal::SizedSample + cpal::FromSample<f32>>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        track: Arc<Track>,
+        position: Arc<AtomicU64>,
+    ) -> Result<cpal::Stream, Error> {
+        let channels = config.channels as usize;
+        let step = track.rate() as f64 / config.sample_rate.0 as f64;
+
+        device
+            .build_output_stream(
+                config,
+                move |data: &mut [T], _| {
+                    let start = position.load(Ordering::Relaxed);
+                    let mut frame = f64::from_bits(start);
+
+                    for output in data.chunks_mut(channels) {
+                        for (channel, sample) in output.iter_mut().enumerate() {
+                            *sample = T::from_sample(track.sample(frame as i64, channel));
+                        }
+
+                        frame += step;
+                    }
+
+                    // Don't overwrite the position if a seek occured in the meantime
+                    let _ = position.compare_exchange(
+                        start,
+                        frame.to_bits(),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    );
+                },
+                |err| tracing::warn!("An error occured while playing audio: {err}"),
+                None,
+            )
+            .map_err(|err| Error::Audio(err.to_string()))
+    }
+
+    pub fn track(&self) -> &Arc<Track> {
+        &self.track
+    }
+
+    /// Seek the playback to `time` if it drifted too far away from it.
+    pub fn sync(&self, time: f32) {
+        let rate = self.track.rate() as f64;
+        let target = time as f64 * rate;
+        let current = f64::from_bits(self.position.load(Ordering::Relaxed));
+
+        if (current - target).abs() > Self::DRIFT * rate {
+            self.position.store(target.to_bits(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// An audio file played along the shader, and analysed into a texture every frame.
+pub struct Audio {
+    path: PathBuf,
+    player: Player,
+
+    fft: Arc<dyn rustfft::Fft<f32>>,
+    spectrum: Vec<f32>,
+    levels: Levels,
+    average: f32,
+    last_beat: f32,
+
+    texture: Option<AllocGuard<glow::Texture>>,
+}
+
+impl std::fmt::Debug for Audio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Audio")
+            .field("path", &self.path)
+            .field("player", &self.player)
+            .field("levels", &self.levels)
+            .finish()
+    }
+}
+
+impl Audio {
+    /// The width of the uploaded texture, the FFT is computed on twice as many samples.
+    pub const WIDTH: usize = 512;
+
+    /// The smoothing factor applied to the spectrum between frames.
+    const SMOOTHING: f32 = 0.8;
+
+    /// The decibel range mapped to the `[0; 1]` range in the texture.
+    const DECIBELS: std::ops::RangeInclusive<f32> = -100.0..=-30.0;
+
+    pub fn load(path: PathBuf) -> Result<Self, Error> {
+        tracing::info!("Loading audio file from `{}`", path.display());
+
+        let player = Player::new(Arc::new(Track::load(&path)?))?;
+
+        Ok(Self {
+            path,
+            player,
+            fft: rustfft::FftPlanner::new().plan_fft_forward(Self::WIDTH * 2),
+            spectrum: vec![0.0; Self::WIDTH],
+            levels: Default::default(),
+            average: 0.0,
+            last_beat: 0.0,
+            texture: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.player.track().duration()
+    }
+
+    /// Synchronize the playback with `time`, and upload the spectrum and waveform at that time to the texture.
+    pub fn update(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        time: f32,
+    ) -> Result<(Levels, glow::Texture), Error> {
+        self.player.sync(time);
+
+        let track = self.player.track().clone();
+        let size = Self::WIDTH * 2;
+        let end = (time as f64 * track.rate() as f64) as i64;
+        let start = end - size as i64;
+
+        // Blackman-windowed FFT over the samples preceding `time`
+        let mut buffer = (0..size)
+            .map(|i| {
+                let x = i as f32 / size as f32 * std::f32::consts::TAU;
+                let window = 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos();
+
+                Complex::new(track.mono(start + i as i64) * window, 0.0)
+            })
+            .collect::<Vec<_>>();
+        self.fft.process(&mut buffer);
+
+        for (smoothed, bin) in self.spectrum.iter_mut().zip(&buffer) {
+            *smoothed =
+                Self::SMOOTHING * *smoothed + (1.0 - Self::SMOOTHING) * bin.norm() / size as f32;
+        }
+
+        let mut pixels = self
+            .spectrum
+            .iter()
+            .map(|magnitude| {
+                let decibels = 20.0 * magnitude.max(f32::MIN_POSITIVE).log10();
+
+                ((decibels - Self::DECIBELS.start())
+                    / (Self::DECIBELS.end() - Self::DECIBELS.start()))
+                .clamp(0.0, 1.0)
+            })
+            .collect::<Vec<_>>();
+
+        self.levels = self.levels(time, &pixels, track.rate());
+
+        pixels.extend(
+            (end - Self::WIDTH as i64..end)
+                .map(|frame| (0.5 + 0.5 * track.mono(frame)).clamp(0.0, 1.0)),
+        );
+
+        let texture = match &self.texture {
+            Some(texture) => **texture,
+            None => unsafe {
+                let texture = guard!(
+                    gl,
+                    gl.create_texture().map_err(Error::Gl)?,
+                    move |texture| gl.delete_texture(texture)
+                );
+
+                gl.bind_texture(glow::TEXTURE_2D, Some(*texture));
+                gl.tex_parameter_i32(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_MIN_FILTER,
+                    glow::LINEAR as i32,
+                );
+                gl.tex_parameter_i32(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_MAG_FILTER,
+                    glow::LINEAR as i32,
+                );
+                gl.tex_parameter_i32(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_WRAP_S,
+                    glow::CLAMP_TO_EDGE as i32,
+                );
+                gl.tex_parameter_i32(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_WRAP_T,
+                    glow::CLAMP_TO_EDGE as i32,
+                );
+
+                **self.texture.insert(texture)
+            },
+        };
+
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::R32F as i32,
+                Self::WIDTH as i32,
+                2,
+                0,
+                glow::RED,
+                glow::FLOAT,
+                Some(bytemuck::cast_slice(&pixels)),
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+
+        Ok((self.levels, texture))
+    }
+
+    fn levels(&mut self, time: f32, spectrum: &[f32], rate: u32) -> Levels {
+        let band = |range: std::ops::Range<f32>| {
+            let hertz = rate as f32 / (Self::WIDTH * 2) as f32;
+            let end = ((range.end / hertz) as usize).min(spectrum.len());
+            let bins = &spectrum[((range.start / hertz) as usize).min(end)..end];
+
+            bins.iter().sum::<f32>() / bins.len().max(1) as f32
+        };
+
+        let bass = band(20.0..250.0);
+
+        // Seeking backwards invalidates the beat history
+        if time < self.last_beat {
+            self.last_beat = 0.0;
+        }
+
+        let beat = bass > 0.1 && bass > self.average * 1.3 && time - self.last_beat > 0.2;
+        if beat {
+            self.last_beat = time;
+        }
+        self.average = 0.95 * self.average + 0.05 * bass;
+
+        Levels {
+            bass,
+            mid: band(250.0..4000.0),
+            treble: band(4000.0..16000.0),
+            beat,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track() {
+        // Three stereo frames, at four frames per second
+        let track = Track::new(vec![0.1, 0.2, 0.3, 0.6, -0.5, -1.0], 2, 4);
+
+        assert_eq!(track.frames(), 3);
+        assert_eq!(track.duration(), 0.75);
+
+        assert_eq!(track.sample(1, 0), 0.3);
+        assert_eq!(track.sample(1, 1), 0.6);
+        // The channels past the last one read the last one
+        assert_eq!(track.sample(2, 5), -1.0);
+
+        assert!((track.mono(0) - 0.15).abs() < 1e-6);
+        assert!((track.mono(2) + 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn silence() {
+        let track = Track::new(vec![0.5, -0.5], 1, 44100);
+
+        assert_eq!(track.sample(-1, 0), 0.0);
+        assert_eq!(track.sample(2, 0), 0.0);
+        assert_eq!(track.mono(-512), 0.0);
+        assert_eq!(track.mono(1), -0.5);
+    }
+}
@@ -5,16 +5,138 @@ use eframe::{
     glow::{self, HasContext},
 };
 
-use crate::{guard, AllocGuard};
+use crate::{guard, renderer::Pool, AllocGuard};
 
-pub struct Canvas(Option<AllocGuard<glow::Texture>>, egui::Painter);
+/// The zoom and pan of the canvas, a pure view transform over the rendered texture.
+#[derive(Debug, Clone, Copy)]
+pub struct View {
+    /// The magnification of the texture, from `1` to [`View::MAX_ZOOM`].
+    pub zoom: f32,
+    /// The position of the texture origin in the canvas, in OpenGL pixel coordinates.
+    pub offset: egui::Vec2,
+    /// Outline the pixels once magnified at least [`View::GRID_ZOOM`] times.
+    pub grid: bool,
+
+    /// The size of the canvas, in physical pixels.
+    size: egui::Vec2,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            offset: egui::Vec2::ZERO,
+            grid: true,
+            size: egui::Vec2::ZERO,
+        }
+    }
+}
+
+impl View {
+    pub const MAX_ZOOM: f32 = 64.0;
+    pub const GRID_ZOOM: f32 = 8.0;
+
+    /// Reset to the 1:1 view, keeping the settings.
+    pub fn reset(&mut self) {
+        *self = Self {
+            grid: self.grid,
+            size: self.size,
+            ..Default::default()
+        };
+    }
+
+    /// Zoom with the scroll wheel around the cursor, pan by dragging and reset on double-click,
+    /// the canvas covering the `viewport` in physical pixels.
+    pub fn interact(&mut self, ui: &egui::Ui, response: &egui::Response, viewport: egui::Rect) {
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        self.size = viewport.size();
+
+        if response.double_clicked() {
+            self.reset();
+        }
+
+        if response.dragged() {
+            let delta = response.drag_delta() * pixels_per_point;
+
+            self.offset += egui::vec2(delta.x, -delta.y);
+        }
+
+        if let Some(pos) = response.hover_pos() {
+            let (scroll, zoom) = ui.input(|input| (input.scroll_delta.y, input.zoom_delta()));
+
+            self.zoom_around(
+                Self::local(viewport, pos, pixels_per_point),
+                self.zoom * zoom * (scroll / 200.0).exp(),
+            );
+        }
+
+        // The texture always covers the whole canvas
+        self.offset = self
+            .offset
+            .clamp(viewport.size() * (1.0 - self.zoom), egui::Vec2::ZERO);
+    }
+
+    /// Set the magnification to `zoom`, around the center of the canvas.
+    pub fn zoom_to(&mut self, zoom: f32) {
+        self.zoom_around((self.size / 2.0).to_pos2(), zoom);
+    }
+
+    /// Set the magnification to `zoom`, keeping the texture point at `anchor` in place.
+    fn zoom_around(&mut self, anchor: egui::Pos2, zoom: f32) {
+        let zoom = zoom.clamp(1.0, Self::MAX_ZOOM);
+
+        self.offset = anchor.to_vec2() - (anchor.to_vec2() - self.offset) * (zoom / self.zoom);
+        self.zoom = zoom;
+    }
+
+    /// The position of the screen point `pos` in the canvas covering `viewport`, in OpenGL pixel coordinates.
+    pub fn local(viewport: egui::Rect, pos: egui::Pos2, pixels_per_point: f32) -> egui::Pos2 {
+        let pos = pos * pixels_per_point - viewport.min;
+
+        egui::pos2(pos.x, viewport.height() - pos.y)
+    }
+
+    /// The position in the texture displayed at `pos` in the canvas, in OpenGL pixel coordinates.
+    pub fn texture_pos(&self, pos: egui::Pos2) -> egui::Pos2 {
+        (pos - self.offset) / self.zoom
+    }
+
+    /// The range of texels visible in a canvas of `size`, as the first and last-plus-one texel on each axis.
+    fn visible(&self, size: egui::Vec2) -> (egui::Pos2, egui::Pos2) {
+        let min = self
+            .texture_pos(egui::Pos2::ZERO)
+            .floor()
+            .max(egui::Pos2::ZERO);
+        let max = self.texture_pos(size.to_pos2()).ceil().min(size.to_pos2());
+
+        (min, max)
+    }
+}
+
+pub struct Canvas(
+    Option<AllocGuard<glow::Texture>>,
+    egui::Painter,
+    egui::Response,
+    View,
+);
 
 impl Canvas {
-    pub fn new(texture: Option<AllocGuard<glow::Texture>>, painter: egui::Painter) -> Self {
-        Self(texture, painter)
+    pub fn new(
+        texture: Option<AllocGuard<glow::Texture>>,
+        painter: egui::Painter,
+        response: egui::Response,
+        view: View,
+    ) -> Self {
+        Self(texture, painter, response, view)
+    }
+
+    pub fn response(&self) -> &egui::Response {
+        &self.2
     }
 
     pub fn paint(mut self) {
+        let view = self.3;
+
         if let Some(texture) = self.0.take() {
             // This removes the guard, but we'll re-add it after thread boudary
             let texture = AllocGuard::into_inner(texture);
@@ -25,20 +147,29 @@ impl Canvas {
                     move |info, painter| unsafe {
                         let gl = painter.gl();
 
-                        // Readded allocation guard to ensure the resource is deleted after use
+                        // Readded allocation guard to ensure the resource is released after use
                         let texture =
-                            guard!(gl, texture, move |texture| gl.delete_texture(texture));
+                            guard!(gl, texture, move |texture| Pool::release(&gl, texture));
 
-                        let buffer = guard!(
-                            gl,
-                            gl.create_framebuffer()
-                                .expect("Unable to create frame buffer"),
-                            move |buffer| gl.delete_framebuffer(buffer)
-                        );
+                        // Reuse the framebuffer of the render target, if the texture is one
+                        let created;
+                        let buffer = match Pool::framebuffer(*texture) {
+                            Some(buffer) => buffer,
+                            None => {
+                                created = guard!(
+                                    gl,
+                                    gl.create_framebuffer()
+                                        .expect("Unable to create frame buffer"),
+                                    move |buffer| gl.delete_framebuffer(buffer)
+                                );
+
+                                *created
+                            }
+                        };
 
                         painter
                             .gl()
-                            .bind_framebuffer(glow::READ_FRAMEBUFFER, Some(*buffer));
+                            .bind_framebuffer(glow::READ_FRAMEBUFFER, Some(buffer));
                         painter.gl().framebuffer_texture_2d(
                             glow::READ_FRAMEBUFFER,
                             glow::COLOR_ATTACHMENT0,
@@ -55,16 +186,26 @@ impl Canvas {
                                 == glow::FRAMEBUFFER_COMPLETE
                         );
 
+                        // Only blit the visible texels, magnified without filtering
                         let viewport = info.viewport_in_pixels();
+                        let (min, max) = view.visible(egui::vec2(
+                            viewport.width_px as f32,
+                            viewport.height_px as f32,
+                        ));
+                        let (from, to) = (
+                            view.offset + min.to_vec2() * view.zoom,
+                            view.offset + max.to_vec2() * view.zoom,
+                        );
+
                         painter.gl().blit_framebuffer(
-                            0,
-                            0,
-                            viewport.width_px,
-                            viewport.height_px,
-                            viewport.left_px,
-                            viewport.from_bottom_px,
-                            viewport.left_px + viewport.width_px,
-                            viewport.from_bottom_px + viewport.height_px,
+                            min.x as i32,
+                            min.y as i32,
+                            max.x as i32,
+                            max.y as i32,
+                            viewport.left_px + from.x.round() as i32,
+                            viewport.from_bottom_px + from.y.round() as i32,
+                            viewport.left_px + to.x.round() as i32,
+                            viewport.from_bottom_px + to.y.round() as i32,
                             glow::COLOR_BUFFER_BIT,
                             glow::NEAREST,
                         );
@@ -73,6 +214,40 @@ impl Canvas {
                     }
                 })),
             });
+
+            if view.grid && view.zoom >= View::GRID_ZOOM {
+                self.grid(view);
+            }
+        }
+    }
+
+    /// Outline the visible texels.
+    fn grid(&self, view: View) {
+        let rect = self.1.clip_rect();
+        let pixels_per_point = self.1.ctx().pixels_per_point();
+        let size = rect.size() * pixels_per_point;
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_black_alpha(96));
+
+        // From OpenGL pixel coordinates of the canvas to screen points
+        let screen =
+            |pos: egui::Pos2| rect.min + egui::vec2(pos.x, size.y - pos.y) / pixels_per_point;
+
+        let (min, max) = view.visible(size);
+        for x in min.x as i32..=max.x as i32 {
+            let x = view.offset.x + x as f32 * view.zoom;
+
+            self.1.line_segment(
+                [screen(egui::pos2(x, 0.0)), screen(egui::pos2(x, size.y))],
+                stroke,
+            );
+        }
+        for y in min.y as i32..=max.y as i32 {
+            let y = view.offset.y + y as f32 * view.zoom;
+
+            self.1.line_segment(
+                [screen(egui::pos2(0.0, y)), screen(egui::pos2(size.x, y))],
+                stroke,
+            );
         }
     }
 }
@@ -8,6 +8,45 @@ pub enum Error {
     #[error("{0}")]
     Gl(String),
 
+    #[cfg(feature = "gui")]
+    #[error(transparent)]
+    Context(#[from] glutin::error::Error),
+
     #[error("{0}")]
     Compile(String),
+
+    #[error("No shader was loaded")]
+    Unloaded,
+
+    #[cfg(feature = "gui")]
+    #[error("{0}")]
+    Interpret(String),
+
+    #[cfg(feature = "gui")]
+    #[error("{0}")]
+    Midi(String),
+
+    #[cfg(feature = "gui")]
+    #[error("{0}")]
+    Audio(String),
+
+    #[cfg(feature = "gui")]
+    #[error("{0}")]
+    Ndi(String),
+
+    #[cfg(feature = "gui")]
+    #[error(transparent)]
+    Decode(#[from] symphonia::core::errors::Error),
+
+    #[cfg(feature = "gui")]
+    #[error(transparent)]
+    Wav(#[from] hound::Error),
+
+    #[cfg(feature = "gui")]
+    #[error(transparent)]
+    Parse(#[from] ron::error::SpannedError),
+
+    #[cfg(feature = "gui")]
+    #[error(transparent)]
+    Serialize(#[from] ron::Error),
 }
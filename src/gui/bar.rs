@@ -1,12 +1,14 @@
 use eframe::egui;
 
-use crate::{Renderer, Shader};
+use crate::{audio::Audio, renderer::Sound, Error, Renderer, Shader};
 
 #[derive(Debug, Default)]
 pub struct Bar;
 
 impl Bar {
-    pub fn show(&self, ctx: &egui::Context, renderer: &mut Renderer) {
+    pub fn show(&self, ctx: &egui::Context, renderer: &mut Renderer) -> Option<Error> {
+        let mut error = None;
+
         egui::TopBottomPanel::top("bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 egui::widgets::global_dark_light_mode_switch(ui);
@@ -29,6 +31,61 @@ impl Bar {
                         renderer.shader = None;
                     }
 
+                    if ui.button("Load comparison shader..").clicked() {
+                        ui.close_menu();
+
+                        renderer.comparison.shader = rfd::FileDialog::new()
+                            .set_title("Select comparison shader")
+                            .pick_file()
+                            .map(Shader::new);
+                    }
+
+                    if ui.button("Clear comparison shader..").clicked() {
+                        ui.close_menu();
+
+                        renderer.comparison.shader = None;
+                    }
+
+                    ui.separator();
+
+                    if ui.button("Load audio..").clicked() {
+                        ui.close_menu();
+
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title("Select audio file")
+                            .add_filter("Audio", &["wav", "flac", "ogg"])
+                            .pick_file()
+                        {
+                            match Audio::load(path) {
+                                Ok(audio) => renderer.audio = Some(audio),
+                                Err(err) => error = Some(err),
+                            }
+                        }
+                    }
+
+                    if ui.button("Clear audio..").clicked() {
+                        ui.close_menu();
+
+                        renderer.audio = None;
+                    }
+
+                    ui.separator();
+
+                    if ui.button("Load sound shader..").clicked() {
+                        ui.close_menu();
+
+                        renderer.sound = rfd::FileDialog::new()
+                            .set_title("Select sound shader")
+                            .pick_file()
+                            .map(Sound::new);
+                    }
+
+                    if ui.button("Clear sound shader..").clicked() {
+                        ui.close_menu();
+
+                        renderer.sound = None;
+                    }
+
                     ui.separator();
 
                     if ui.button("Quit").clicked() {
@@ -39,5 +96,7 @@ impl Bar {
                 });
             });
         });
+
+        error
     }
 }
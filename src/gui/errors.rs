@@ -1,29 +1,34 @@
 use eframe::egui;
 
-use super::Error;
+use crate::renderer;
 
 #[derive(Debug, Default)]
-pub struct Errors(pub Option<Error>);
+pub struct Errors(pub renderer::Errors);
 
 impl Errors {
     pub fn show(&self, ctx: &egui::Context) {
+        let renderer::Errors { main, tools } = &self.0;
+
         egui::TopBottomPanel::bottom("errors").show(ctx, |ui| {
             egui::CollapsingHeader::new("⚠ Errors")
                 .default_open(true)
-                .show(ui, |ui| match &self.0 {
-                    None => ui.label(
-                        egui::RichText::new("There are no errors for now ✔")
-                            .italics()
-                            .weak(),
-                    ),
-                    Some(error) => {
+                .show(ui, |ui| {
+                    if main.is_none() && tools.is_empty() {
+                        ui.label(
+                            egui::RichText::new("There are no errors for now ✔")
+                                .italics()
+                                .weak(),
+                        );
+                    }
+
+                    for error in main.iter().chain(tools.values()) {
                         let error = error.to_string();
                         let error = error
                             .strip_suffix("\r\n")
                             .or(error.strip_suffix('\n'))
                             .unwrap_or(&error);
 
-                        ui.monospace(error)
+                        ui.monospace(error);
                     }
                 });
         });
@@ -0,0 +1,110 @@
+use eframe::egui;
+
+use crate::renderer::{self, Inspection};
+
+#[derive(Debug, Default)]
+pub struct Inspector {
+    tooltip: bool,
+}
+
+impl Inspector {
+    /// Show the values of the hovered pixel, only read back while they're shown.
+    pub fn show(&mut self, ui: &mut egui::Ui, inspector: &mut renderer::Inspector) {
+        let shown = ui.collapsing("🔍 Inspector", |ui| {
+            ui.label("Rendered values of the pixel under the cursor.");
+
+            ui.checkbox(&mut self.tooltip, "Show next to the cursor");
+
+            match inspector.inspection() {
+                Some(inspection) => Self::values(ui, inspection),
+                None => {
+                    ui.label(
+                        egui::RichText::new("Hover the canvas to inspect a pixel")
+                            .italics()
+                            .weak(),
+                    );
+                }
+            }
+        });
+
+        inspector.enabled = shown.body_returned.is_some() || self.tooltip;
+    }
+
+    pub fn hover(&self, response: &egui::Response, inspection: Option<&Inspection>) {
+        if let (true, Some(inspection)) = (self.tooltip, inspection) {
+            response
+                .clone()
+                .on_hover_ui_at_pointer(|ui| Self::values(ui, inspection));
+        }
+    }
+
+    fn values(ui: &mut egui::Ui, inspection: &Inspection) {
+        let [r, g, b, a] = inspection.rgba;
+        let [r8, g8, b8, a8] = inspection.rgba8();
+
+        egui::Grid::new("inspection")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("gl_FragCoord");
+                ui.code(format!(
+                    "{:.1}, {:.1}",
+                    inspection.coord.x, inspection.coord.y
+                ));
+                ui.end_row();
+
+                ui.strong("UV");
+                ui.code(format!("{:.4}, {:.4}", inspection.uv.x, inspection.uv.y));
+                ui.end_row();
+
+                ui.strong("RGBA");
+                ui.code(format!("{r:.4}, {g:.4}, {b:.4}, {a:.4}"));
+                ui.end_row();
+
+                ui.strong("8-bit");
+                ui.code(format!("{r8}, {g8}, {b8}, {a8}"));
+                ui.end_row();
+
+                ui.strong("Hex");
+                ui.code(inspection.hex());
+                ui.end_row();
+            });
+
+        // Magnified view of the neighbourhood, the hovered pixel being at the center
+        let cell = 12.0;
+        let (rect, _) = ui.allocate_exact_size(
+            egui::Vec2::splat(cell * Inspection::SIZE as f32),
+            egui::Sense::hover(),
+        );
+
+        for (row, line) in inspection.neighbourhood.iter().enumerate() {
+            for (column, value) in line.iter().enumerate() {
+                let cell = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(column as f32, row as f32) * cell,
+                    egui::Vec2::splat(cell),
+                );
+
+                // The shader values are displayed as-is on the canvas, so are they here
+                let color = value
+                    .map(|rgba| {
+                        let [r, g, b, _] =
+                            rgba.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+                        egui::Color32::from_rgb(r, g, b)
+                    })
+                    .unwrap_or(egui::Color32::TRANSPARENT);
+                ui.painter().rect_filled(cell, 0.0, color);
+            }
+        }
+
+        let center = Inspection::SIZE as f32 / 2.0;
+        ui.painter().rect_stroke(
+            egui::Rect::from_center_size(
+                rect.min + egui::Vec2::splat(center * cell),
+                egui::Vec2::splat(cell),
+            ),
+            0.0,
+            ui.visuals().selection.stroke,
+        );
+    }
+}
@@ -6,6 +6,21 @@ use bar::Bar;
 mod errors;
 use errors::Errors;
 
+mod inspector;
+use inspector::Inspector;
+
+mod performance;
+use performance::Performance;
+
+mod reference;
+use reference::Reference;
+
+mod stepper;
+use stepper::StepThrough;
+
+mod tabs;
+use tabs::Tabs;
+
 mod tools;
 use tools::Tools;
 
@@ -14,6 +29,7 @@ use super::{Error, Renderer};
 #[derive(Debug, Default)]
 pub struct Gui {
     bar: Bar,
+    tabs: Tabs,
     tools: Tools,
     errors: Errors,
     live_mode: bool,
@@ -26,17 +42,50 @@ impl Gui {
         }
 
         if !self.live_mode {
-            self.bar.show(ctx, renderer);
-            self.tools.show(ctx, renderer);
+            if let Some(err) = self.bar.show(ctx, renderer) {
+                tracing::warn!("An error occured in the menu bar: {err}");
+
+                self.set_error(err);
+            }
+            self.tabs.show(ctx, renderer, &mut self.errors.0);
+            if let Some(err) = self.tools.show(ctx, renderer) {
+                tracing::warn!("An error occured in the tools panel: {err}");
+
+                self.set_error(err);
+            }
             self.errors.show(ctx);
+        } else {
+            self.tools.performance.overlay(ctx, &renderer.timings);
         }
     }
 
+    /// Show the hovered pixel values next to the cursor, if enabled.
+    pub fn hover(&self, response: &egui::Response, renderer: &Renderer) {
+        self.tools
+            .inspector
+            .hover(response, renderer.inspector.inspection());
+    }
+
     pub fn set_error(&mut self, error: Error) {
-        self.errors.0 = Some(error);
+        self.errors.0.main = Some(error);
     }
 
     pub fn clear_error(&mut self) {
-        self.errors.0 = None;
+        self.errors.0.main = None;
+    }
+
+    /// Keep the error of the `tool` until it updates successfully, `result` telling whether it did.
+    pub fn report(&mut self, tool: &'static str, result: Result<bool, Error>) {
+        match result {
+            Ok(true) => {
+                self.errors.0.tools.remove(tool);
+            }
+            Ok(false) => (),
+            Err(err) => {
+                tracing::warn!("An error occured while updating the {tool}: {err}");
+
+                self.errors.0.tools.insert(tool, err);
+            }
+        }
     }
 }
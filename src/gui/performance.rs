@@ -0,0 +1,125 @@
+use eframe::egui;
+
+use crate::renderer::{Frame, Timings};
+
+#[derive(Debug, Default)]
+pub struct Performance;
+
+impl Performance {
+    /// The frame time at the top of the graph, in milliseconds, unless exceeded.
+    const SCALE: f32 = 1000.0 / 30.0;
+
+    pub fn show(&mut self, ui: &mut egui::Ui, timings: &mut Timings) {
+        ui.collapsing("⏱ Performance", |ui| {
+            ui.label("Frame times on the CPU and the GPU, averaged over the last second.");
+
+            ui.checkbox(&mut timings.overlay, "Show in live mode");
+
+            let Some(average) = timings.average() else {
+                return;
+            };
+
+            egui::Grid::new("performance")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Framerate");
+                    ui.code(format!("{:.1} fps", 1000.0 / average.interval));
+                    ui.end_row();
+
+                    ui.strong("CPU");
+                    ui.code(format!("{:.2} ms", average.cpu));
+                    ui.end_row();
+
+                    ui.strong("GPU");
+                    ui.code(format!("{:.2} ms", average.gpu()));
+                    ui.end_row();
+
+                    if average.passes.len() > 1 {
+                        for (pass, time) in &average.passes {
+                            ui.label(format!("    {pass}"));
+                            ui.code(format!("{time:.2} ms"));
+                            ui.end_row();
+                        }
+                    }
+                });
+
+            Self::graph(ui, timings.frames());
+        });
+    }
+
+    /// Show the minimal timings over the canvas, in live mode.
+    pub fn overlay(&self, ctx: &egui::Context, timings: &Timings) {
+        let (true, Some(average)) = (timings.overlay, timings.average()) else {
+            return;
+        };
+
+        egui::Area::new("performance-overlay")
+            .anchor(egui::Align2::LEFT_TOP, egui::vec2(8.0, 8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.monospace(format!(
+                        "{:.0} fps  CPU {:.2} ms  GPU {:.2} ms",
+                        1000.0 / average.interval,
+                        average.cpu,
+                        average.gpu()
+                    ));
+                });
+            });
+    }
+
+    /// Plot the frame intervals and GPU times of the history, the latest on the right.
+    fn graph<'a>(ui: &mut egui::Ui, frames: impl ExactSizeIterator<Item = &'a Frame> + Clone) {
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 80.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+
+        let scale = frames
+            .clone()
+            .map(|frame| frame.interval.max(frame.gpu()))
+            .fold(Self::SCALE, f32::max);
+        let step = rect.width() / (Timings::HISTORY - 1) as f32;
+        let offset = Timings::HISTORY - frames.len();
+
+        let points = |time: fn(&Frame) -> f32| {
+            frames
+                .clone()
+                .enumerate()
+                .map(|(index, frame)| {
+                    egui::pos2(
+                        rect.left() + (offset + index) as f32 * step,
+                        rect.bottom() - time(frame) / scale * rect.height(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        // The 60 fps frame time
+        let target = rect.bottom() - 1000.0 / 60.0 / scale * rect.height();
+        painter.hline(
+            rect.x_range(),
+            target,
+            egui::Stroke::new(1.0, ui.visuals().weak_text_color()),
+        );
+
+        let (frame, gpu) = (
+            egui::Color32::from_rgb(100, 160, 255),
+            egui::Color32::from_rgb(255, 140, 60),
+        );
+        painter.add(egui::Shape::line(
+            points(|frame| frame.interval),
+            egui::Stroke::new(1.0, frame),
+        ));
+        painter.add(egui::Shape::line(
+            points(Frame::gpu),
+            egui::Stroke::new(1.0, gpu),
+        ));
+
+        response.on_hover_text(format!(
+            "Frame time (blue) and GPU time (orange), up to {scale:.1} ms.\nThe line marks 60 fps."
+        ));
+    }
+}
@@ -0,0 +1,901 @@
+use eframe::egui;
+
+/// A plotted expression of `x`, along with its evaluation.
+type Plot = (&'static str, fn(f32) -> f32);
+
+/// A built-in function or type of the GLSL language.
+#[derive(Debug)]
+pub struct Entry {
+    pub name: &'static str,
+    pub signatures: &'static [&'static str],
+    pub description: &'static str,
+    /// The first GLSL version supporting it, as in the `#version` directive.
+    pub since: u16,
+    /// The version it was removed from the core profile in, if any.
+    pub removed: Option<u16>,
+    pub plot: Option<Plot>,
+}
+
+impl Entry {
+    /// The versions supporting it, in a readable form.
+    pub fn versions(&self) -> String {
+        let version = |version: u16| format!("{}.{:02}", version / 100, version % 100);
+
+        match self.removed {
+            Some(removed) => format!("GLSL {} to {}", version(self.since), version(removed)),
+            None => format!("GLSL {}+", version(self.since)),
+        }
+    }
+
+    fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+
+        self.name.to_lowercase().contains(&search)
+            || self.description.to_lowercase().contains(&search)
+    }
+}
+
+#[derive(Debug)]
+pub struct Reference {
+    search: String,
+    selected: Option<&'static Entry>,
+    /// The plotted range of `x`.
+    domain: (f32, f32),
+}
+
+impl Default for Reference {
+    fn default() -> Self {
+        Self {
+            search: String::new(),
+            selected: None,
+            domain: (-2.0, 2.0),
+        }
+    }
+}
+
+impl Reference {
+    /// The number of samples of the plots.
+    const SAMPLES: usize = 256;
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("📖 Reference", |ui| {
+            ui.label("Some documentation about the GLSL methods and types.");
+
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search.."));
+            });
+
+            for (title, entries) in [("Functions", FUNCTIONS), ("Types", TYPES)] {
+                let entries = entries
+                    .iter()
+                    .filter(|entry| entry.matches(&self.search))
+                    .collect::<Vec<_>>();
+
+                if entries.is_empty() {
+                    continue;
+                }
+
+                ui.strong(title);
+                egui::ScrollArea::vertical()
+                    .id_source(title)
+                    .max_height(96.0)
+                    .show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for entry in entries {
+                                let selected = self
+                                    .selected
+                                    .is_some_and(|selected| std::ptr::eq(selected, entry));
+
+                                if ui
+                                    .selectable_label(
+                                        selected,
+                                        egui::RichText::new(entry.name).code(),
+                                    )
+                                    .clicked()
+                                {
+                                    self.selected = (!selected).then_some(entry);
+                                }
+                            }
+                        });
+                    });
+            }
+
+            let Some(entry) = self.selected else {
+                return;
+            };
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.heading(entry.name);
+                ui.weak(entry.versions());
+            });
+            for signature in entry.signatures {
+                ui.code(*signature);
+            }
+            ui.label(entry.description);
+
+            if let Some((expression, function)) = entry.plot {
+                ui.horizontal(|ui| {
+                    ui.strong("Domain");
+                    ui.add(egui::DragValue::new(&mut self.domain.0).speed(0.1));
+                    ui.label("to");
+                    ui.add(egui::DragValue::new(&mut self.domain.1).speed(0.1));
+                });
+                if self.domain.1 <= self.domain.0 {
+                    self.domain.1 = self.domain.0 + 0.1;
+                }
+
+                Self::plot(ui, expression, function, self.domain);
+            }
+        });
+    }
+
+    /// Plot `function` over `domain`, leaving gaps where it is undefined.
+    fn plot(ui: &mut egui::Ui, expression: &str, function: fn(f32) -> f32, domain: (f32, f32)) {
+        let samples = (0..=Self::SAMPLES)
+            .map(|index| {
+                let x = domain.0 + (domain.1 - domain.0) * index as f32 / Self::SAMPLES as f32;
+
+                (x, function(x))
+            })
+            .collect::<Vec<_>>();
+
+        let (low, high) = samples
+            .iter()
+            .map(|(_, y)| *y)
+            .filter(|y| y.is_finite())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), y| {
+                (low.min(y), high.max(y))
+            });
+        let (low, high) = match (low.is_finite(), high - low) {
+            (false, _) => (-1.0, 1.0),
+            (true, extent) if extent < 1e-3 => (low - 1.0, high + 1.0),
+            (true, extent) => (low - extent * 0.1, high + extent * 0.1),
+        };
+
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), 120.0),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+
+        let position = |x: f32, y: f32| {
+            egui::pos2(
+                egui::remap(x, domain.0..=domain.1, rect.x_range()),
+                egui::remap(y, low..=high, rect.bottom()..=rect.top()),
+            )
+        };
+
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+        // The axes, when in range
+        let axis = egui::Stroke::new(1.0, ui.visuals().weak_text_color());
+        if (domain.0..=domain.1).contains(&0.0) {
+            painter.vline(position(0.0, 0.0).x, rect.y_range(), axis);
+        }
+        if (low..=high).contains(&0.0) {
+            painter.hline(rect.x_range(), position(0.0, 0.0).y, axis);
+        }
+
+        let stroke = egui::Stroke::new(1.5, ui.visuals().selection.stroke.color);
+        for segment in samples.split(|(_, y)| !y.is_finite()) {
+            painter.add(egui::Shape::line(
+                segment.iter().map(|(x, y)| position(*x, *y)).collect(),
+                stroke,
+            ));
+        }
+
+        response.on_hover_text(format!(
+            "y = {expression}, for x from {:.2} to {:.2} and y from {low:.2} to {high:.2}",
+            domain.0, domain.1
+        ));
+    }
+}
+
+/// The GLSL `mod`, which unlike `%` follows the sign of `y`.
+fn modulo(x: f32, y: f32) -> f32 {
+    x - y * (x / y).floor()
+}
+
+/// The GLSL `smoothstep`, a Hermite interpolation between `edge0` and `edge1`.
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// The GLSL `roundEven`, rounding halves to the nearest even integer.
+fn round_even(x: f32) -> f32 {
+    let rounded = x.round();
+
+    if (x - x.trunc()).abs() == 0.5 && modulo(rounded, 2.0) != 0.0 {
+        rounded - x.signum()
+    } else {
+        rounded
+    }
+}
+
+/// The undefined values of the GLSL functions, left out of the plots.
+fn defined(x: f32, condition: bool) -> f32 {
+    if condition {
+        x
+    } else {
+        f32::NAN
+    }
+}
+
+/// The built-in functions, by category.
+const FUNCTIONS: &[Entry] = &[
+    // Angle and trigonometry functions
+    Entry {
+        name: "radians",
+        signatures: &["genType radians(genType degrees)"],
+        description: "Converts degrees to radians, π / 180 × degrees.",
+        since: 110,
+        removed: None,
+        plot: Some(("radians(x)", f32::to_radians)),
+    },
+    Entry {
+        name: "degrees",
+        signatures: &["genType degrees(genType radians)"],
+        description: "Converts radians to degrees, 180 / π × radians.",
+        since: 110,
+        removed: None,
+        plot: Some(("degrees(x)", f32::to_degrees)),
+    },
+    Entry {
+        name: "sin",
+        signatures: &["genType sin(genType angle)"],
+        description: "The standard trigonometric sine of an angle in radians.",
+        since: 110,
+        removed: None,
+        plot: Some(("sin(x)", f32::sin)),
+    },
+    Entry {
+        name: "cos",
+        signatures: &["genType cos(genType angle)"],
+        description: "The standard trigonometric cosine of an angle in radians.",
+        since: 110,
+        removed: None,
+        plot: Some(("cos(x)", f32::cos)),
+    },
+    Entry {
+        name: "tan",
+        signatures: &["genType tan(genType angle)"],
+        description: "The standard trigonometric tangent of an angle in radians.",
+        since: 110,
+        removed: None,
+        plot: Some(("tan(x)", f32::tan)),
+    },
+    Entry {
+        name: "asin",
+        signatures: &["genType asin(genType x)"],
+        description: "The arc sine, the angle whose sine is x, in [-π/2, π/2]. Undefined if |x| > 1.",
+        since: 110,
+        removed: None,
+        plot: Some(("asin(x)", f32::asin)),
+    },
+    Entry {
+        name: "acos",
+        signatures: &["genType acos(genType x)"],
+        description: "The arc cosine, the angle whose cosine is x, in [0, π]. Undefined if |x| > 1.",
+        since: 110,
+        removed: None,
+        plot: Some(("acos(x)", f32::acos)),
+    },
+    Entry {
+        name: "atan",
+        signatures: &["genType atan(genType y, genType x)", "genType atan(genType y_over_x)"],
+        description: "The arc tangent. With two arguments, the angle of the (x, y) vector in [-π, π], using the signs of both to find the quadrant; undefined if both are 0. With one, an angle in [-π/2, π/2].",
+        since: 110,
+        removed: None,
+        plot: Some(("atan(x)", f32::atan)),
+    },
+    Entry {
+        name: "sinh",
+        signatures: &["genType sinh(genType x)"],
+        description: "The hyperbolic sine, (eˣ - e⁻ˣ) / 2.",
+        since: 130,
+        removed: None,
+        plot: Some(("sinh(x)", f32::sinh)),
+    },
+    Entry {
+        name: "cosh",
+        signatures: &["genType cosh(genType x)"],
+        description: "The hyperbolic cosine, (eˣ + e⁻ˣ) / 2.",
+        since: 130,
+        removed: None,
+        plot: Some(("cosh(x)", f32::cosh)),
+    },
+    Entry {
+        name: "tanh",
+        signatures: &["genType tanh(genType x)"],
+        description: "The hyperbolic tangent, sinh(x) / cosh(x).",
+        since: 130,
+        removed: None,
+        plot: Some(("tanh(x)", f32::tanh)),
+    },
+    Entry {
+        name: "asinh",
+        signatures: &["genType asinh(genType x)"],
+        description: "The inverse of the hyperbolic sine.",
+        since: 130,
+        removed: None,
+        plot: Some(("asinh(x)", f32::asinh)),
+    },
+    Entry {
+        name: "acosh",
+        signatures: &["genType acosh(genType x)"],
+        description: "The non-negative inverse of the hyperbolic cosine. Undefined if x < 1.",
+        since: 130,
+        removed: None,
+        plot: Some(("acosh(x)", f32::acosh)),
+    },
+    Entry {
+        name: "atanh",
+        signatures: &["genType atanh(genType x)"],
+        description: "The inverse of the hyperbolic tangent. Undefined if |x| >= 1.",
+        since: 130,
+        removed: None,
+        plot: Some(("atanh(x)", |x| defined(x.atanh(), x.abs() < 1.0))),
+    },
+    // Exponential functions
+    Entry {
+        name: "pow",
+        signatures: &["genType pow(genType x, genType y)"],
+        description: "x raised to the power of y. Undefined if x < 0, or if x = 0 and y <= 0.",
+        since: 110,
+        removed: None,
+        plot: Some(("pow(x, 2.2)", |x| defined(x.powf(2.2), x >= 0.0))),
+    },
+    Entry {
+        name: "exp",
+        signatures: &["genType exp(genType x)"],
+        description: "The natural exponentiation of x, eˣ.",
+        since: 110,
+        removed: None,
+        plot: Some(("exp(x)", f32::exp)),
+    },
+    Entry {
+        name: "log",
+        signatures: &["genType log(genType x)"],
+        description: "The natural logarithm of x. Undefined if x <= 0.",
+        since: 110,
+        removed: None,
+        plot: Some(("log(x)", |x| defined(x.ln(), x > 0.0))),
+    },
+    Entry {
+        name: "exp2",
+        signatures: &["genType exp2(genType x)"],
+        description: "2 raised to the power of x.",
+        since: 110,
+        removed: None,
+        plot: Some(("exp2(x)", f32::exp2)),
+    },
+    Entry {
+        name: "log2",
+        signatures: &["genType log2(genType x)"],
+        description: "The base 2 logarithm of x. Undefined if x <= 0.",
+        since: 110,
+        removed: None,
+        plot: Some(("log2(x)", |x| defined(x.log2(), x > 0.0))),
+    },
+    Entry {
+        name: "sqrt",
+        signatures: &["genType sqrt(genType x)"],
+        description: "The square root of x. Undefined if x < 0.",
+        since: 110,
+        removed: None,
+        plot: Some(("sqrt(x)", f32::sqrt)),
+    },
+    Entry {
+        name: "inversesqrt",
+        signatures: &["genType inversesqrt(genType x)"],
+        description: "The inverse of the square root of x, 1 / sqrt(x). Undefined if x <= 0.",
+        since: 110,
+        removed: None,
+        plot: Some(("inversesqrt(x)", |x| defined(x.sqrt().recip(), x > 0.0))),
+    },
+    // Common functions
+    Entry {
+        name: "abs",
+        signatures: &["genType abs(genType x)", "genIType abs(genIType x)"],
+        description: "The absolute value of x.",
+        since: 110,
+        removed: None,
+        plot: Some(("abs(x)", f32::abs)),
+    },
+    Entry {
+        name: "sign",
+        signatures: &["genType sign(genType x)", "genIType sign(genIType x)"],
+        description: "1 if x > 0, 0 if x = 0 and -1 if x < 0.",
+        since: 110,
+        removed: None,
+        plot: Some(("sign(x)", |x| if x == 0.0 { 0.0 } else { x.signum() })),
+    },
+    Entry {
+        name: "floor",
+        signatures: &["genType floor(genType x)"],
+        description: "The nearest integer less than or equal to x.",
+        since: 110,
+        removed: None,
+        plot: Some(("floor(x)", f32::floor)),
+    },
+    Entry {
+        name: "trunc",
+        signatures: &["genType trunc(genType x)"],
+        description: "The nearest integer to x whose absolute value is not larger than the absolute value of x.",
+        since: 130,
+        removed: None,
+        plot: Some(("trunc(x)", f32::trunc)),
+    },
+    Entry {
+        name: "round",
+        signatures: &["genType round(genType x)"],
+        description: "The nearest integer to x. The direction of the halves is implementation-defined.",
+        since: 130,
+        removed: None,
+        plot: Some(("round(x)", f32::round)),
+    },
+    Entry {
+        name: "roundEven",
+        signatures: &["genType roundEven(genType x)"],
+        description: "The nearest integer to x, rounding the halves to the nearest even integer.",
+        since: 130,
+        removed: None,
+        plot: Some(("roundEven(x)", round_even)),
+    },
+    Entry {
+        name: "ceil",
+        signatures: &["genType ceil(genType x)"],
+        description: "The nearest integer greater than or equal to x.",
+        since: 110,
+        removed: None,
+        plot: Some(("ceil(x)", f32::ceil)),
+    },
+    Entry {
+        name: "fract",
+        signatures: &["genType fract(genType x)"],
+        description: "The fractional part of x, x - floor(x).",
+        since: 110,
+        removed: None,
+        plot: Some(("fract(x)", |x| x - x.floor())),
+    },
+    Entry {
+        name: "mod",
+        signatures: &["genType mod(genType x, float y)", "genType mod(genType x, genType y)"],
+        description: "The modulus of x by y, x - y × floor(x / y), following the sign of y.",
+        since: 110,
+        removed: None,
+        plot: Some(("mod(x, 1.0)", |x| modulo(x, 1.0))),
+    },
+    Entry {
+        name: "modf",
+        signatures: &["genType modf(genType x, out genType i)"],
+        description: "The fractional part of x, with the same sign, storing the integer part in i.",
+        since: 130,
+        removed: None,
+        plot: Some(("modf(x, i)", f32::fract)),
+    },
+    Entry {
+        name: "min",
+        signatures: &["genType min(genType x, genType y)", "genType min(genType x, float y)"],
+        description: "The smallest of x and y.",
+        since: 110,
+        removed: None,
+        plot: Some(("min(x, 0.5)", |x| x.min(0.5))),
+    },
+    Entry {
+        name: "max",
+        signatures: &["genType max(genType x, genType y)", "genType max(genType x, float y)"],
+        description: "The largest of x and y.",
+        since: 110,
+        removed: None,
+        plot: Some(("max(x, 0.5)", |x| x.max(0.5))),
+    },
+    Entry {
+        name: "clamp",
+        signatures: &[
+            "genType clamp(genType x, genType minVal, genType maxVal)",
+            "genType clamp(genType x, float minVal, float maxVal)",
+        ],
+        description: "x constrained between minVal and maxVal, min(max(x, minVal), maxVal). Undefined if minVal > maxVal.",
+        since: 110,
+        removed: None,
+        plot: Some(("clamp(x, 0.0, 1.0)", |x| x.clamp(0.0, 1.0))),
+    },
+    Entry {
+        name: "mix",
+        signatures: &[
+            "genType mix(genType x, genType y, genType a)",
+            "genType mix(genType x, genType y, float a)",
+            "genType mix(genType x, genType y, genBType a)",
+        ],
+        description: "The linear blend of x and y, x × (1 - a) + y × a. With booleans, selects y where a is true (GLSL 1.30+).",
+        since: 110,
+        removed: None,
+        plot: Some(("mix(-1.0, 1.0, x)", |x| x * 2.0 - 1.0)),
+    },
+    Entry {
+        name: "step",
+        signatures: &["genType step(genType edge, genType x)", "genType step(float edge, genType x)"],
+        description: "0 if x < edge, 1 otherwise.",
+        since: 110,
+        removed: None,
+        plot: Some(("step(0.0, x)", |x| if x < 0.0 { 0.0 } else { 1.0 })),
+    },
+    Entry {
+        name: "smoothstep",
+        signatures: &[
+            "genType smoothstep(genType edge0, genType edge1, genType x)",
+            "genType smoothstep(float edge0, float edge1, genType x)",
+        ],
+        description: "0 if x <= edge0, 1 if x >= edge1, and a smooth Hermite interpolation in between. Undefined if edge0 >= edge1.",
+        since: 110,
+        removed: None,
+        plot: Some(("smoothstep(0.0, 1.0, x)", |x| smoothstep(0.0, 1.0, x))),
+    },
+    Entry {
+        name: "isnan",
+        signatures: &["genBType isnan(genType x)"],
+        description: "Whether x is a NaN.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "isinf",
+        signatures: &["genBType isinf(genType x)"],
+        description: "Whether x is a positive or negative infinity.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "floatBitsToInt",
+        signatures: &["genIType floatBitsToInt(genType value)", "genUType floatBitsToUint(genType value)"],
+        description: "The encoding of a floating-point value as an integer, preserving its bits.",
+        since: 330,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "intBitsToFloat",
+        signatures: &["genType intBitsToFloat(genIType value)", "genType uintBitsToFloat(genUType value)"],
+        description: "The floating-point value of the bits of an integer.",
+        since: 330,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "fma",
+        signatures: &["genType fma(genType a, genType b, genType c)"],
+        description: "a × b + c, possibly as a single operation with a single rounding.",
+        since: 400,
+        removed: None,
+        plot: None,
+    },
+    // Geometric functions
+    Entry {
+        name: "length",
+        signatures: &["float length(genType x)"],
+        description: "The length of the vector x.",
+        since: 110,
+        removed: None,
+        plot: Some(("length(x)", f32::abs)),
+    },
+    Entry {
+        name: "distance",
+        signatures: &["float distance(genType p0, genType p1)"],
+        description: "The distance between p0 and p1, length(p0 - p1).",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "dot",
+        signatures: &["float dot(genType x, genType y)"],
+        description: "The dot product of x and y, the sum of their component-wise products.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "cross",
+        signatures: &["vec3 cross(vec3 x, vec3 y)"],
+        description: "The cross product of x and y, perpendicular to both.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "normalize",
+        signatures: &["genType normalize(genType x)"],
+        description: "A vector in the same direction as x, with a length of 1.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "faceforward",
+        signatures: &["genType faceforward(genType N, genType I, genType Nref)"],
+        description: "N if dot(Nref, I) < 0, -N otherwise.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "reflect",
+        signatures: &["genType reflect(genType I, genType N)"],
+        description: "The reflection of the incident vector I on the surface of normal N, I - 2 × dot(N, I) × N. N should be normalized.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "refract",
+        signatures: &["genType refract(genType I, genType N, float eta)"],
+        description: "The refraction of the incident vector I through the surface of normal N, with eta the ratio of the indices of refraction. I and N should be normalized.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    // Matrix functions
+    Entry {
+        name: "matrixCompMult",
+        signatures: &["mat matrixCompMult(mat x, mat y)"],
+        description: "The component-wise product of x and y, unlike the linear algebraic product of `*`.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "outerProduct",
+        signatures: &["matNxM outerProduct(vecM c, vecN r)"],
+        description: "The linear algebraic product of the column vector c by the row vector r.",
+        since: 120,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "transpose",
+        signatures: &["matMxN transpose(matNxM m)"],
+        description: "The transpose of m.",
+        since: 120,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "determinant",
+        signatures: &["float determinant(matN m)"],
+        description: "The determinant of m.",
+        since: 150,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "inverse",
+        signatures: &["matN inverse(matN m)"],
+        description: "The inverse of m. Undefined if m is singular or poorly-conditioned.",
+        since: 140,
+        removed: None,
+        plot: None,
+    },
+    // Vector relational functions
+    Entry {
+        name: "lessThan",
+        signatures: &["bvec lessThan(vec x, vec y)", "bvec lessThanEqual(vec x, vec y)"],
+        description: "The component-wise comparison x < y, or x <= y.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "greaterThan",
+        signatures: &["bvec greaterThan(vec x, vec y)", "bvec greaterThanEqual(vec x, vec y)"],
+        description: "The component-wise comparison x > y, or x >= y.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "equal",
+        signatures: &["bvec equal(vec x, vec y)", "bvec notEqual(vec x, vec y)"],
+        description: "The component-wise comparison x == y, or x != y.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "any",
+        signatures: &["bool any(bvec x)"],
+        description: "Whether any component of x is true.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "all",
+        signatures: &["bool all(bvec x)"],
+        description: "Whether all the components of x are true.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "not",
+        signatures: &["bvec not(bvec x)"],
+        description: "The component-wise logical complement of x.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    // Texture functions
+    Entry {
+        name: "texture",
+        signatures: &[
+            "gvec4 texture(gsampler2D sampler, vec2 P [, float bias])",
+            "gvec4 texture(gsampler3D sampler, vec3 P [, float bias])",
+            "gvec4 texture(gsamplerCube sampler, vec3 P [, float bias])",
+        ],
+        description: "The texel of the sampler at the normalized coordinates P, filtered and wrapped per the sampler parameters.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "texture2D",
+        signatures: &["vec4 texture2D(sampler2D sampler, vec2 coord [, float bias])"],
+        description: "The texel of the sampler at the normalized coordinates coord. Deprecated in favor of texture.",
+        since: 110,
+        removed: Some(140),
+        plot: None,
+    },
+    Entry {
+        name: "texelFetch",
+        signatures: &["gvec4 texelFetch(gsampler2D sampler, ivec2 P, int lod)"],
+        description: "The unfiltered texel of the sampler at the integer coordinates P of the level of detail lod.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "textureSize",
+        signatures: &["ivec2 textureSize(gsampler2D sampler, int lod)"],
+        description: "The dimensions of the level of detail lod of the sampler, in texels.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "textureLod",
+        signatures: &["gvec4 textureLod(gsampler2D sampler, vec2 P, float lod)"],
+        description: "The texel of the sampler at the normalized coordinates P, with an explicit level of detail.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "textureGrad",
+        signatures: &["gvec4 textureGrad(gsampler2D sampler, vec2 P, vec2 dPdx, vec2 dPdy)"],
+        description: "The texel of the sampler at the normalized coordinates P, with explicit gradients to select the level of detail.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    // Fragment processing functions
+    Entry {
+        name: "dFdx",
+        signatures: &["genType dFdx(genType p)", "genType dFdy(genType p)"],
+        description: "The derivative of p in x, or y, in window coordinates, from the differences with the neighbouring fragments.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "fwidth",
+        signatures: &["genType fwidth(genType p)"],
+        description: "The sum of the absolute derivatives of p in x and y, abs(dFdx(p)) + abs(dFdy(p)), handy for antialiasing.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    // Integer functions
+    Entry {
+        name: "bitfieldExtract",
+        signatures: &[
+            "genIType bitfieldExtract(genIType value, int offset, int bits)",
+            "genUType bitfieldExtract(genUType value, int offset, int bits)",
+        ],
+        description: "The bits [offset, offset + bits - 1] of value, in the least significant bits of the result.",
+        since: 400,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "bitCount",
+        signatures: &["genIType bitCount(genIType value)", "genIType bitCount(genUType value)"],
+        description: "The number of bits set to 1 in value.",
+        since: 400,
+        removed: None,
+        plot: None,
+    },
+];
+
+/// The built-in types.
+const TYPES: &[Entry] = &[
+    Entry {
+        name: "bool",
+        signatures: &["bool", "bvec2", "bvec3", "bvec4"],
+        description: "A conditional type, true or false, and vectors of it.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "int",
+        signatures: &["int", "ivec2", "ivec3", "ivec4"],
+        description: "A signed 32-bit integer, and vectors of it.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "uint",
+        signatures: &["uint", "uvec2", "uvec3", "uvec4"],
+        description: "An unsigned 32-bit integer, and vectors of it.",
+        since: 130,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "float",
+        signatures: &["float", "vec2", "vec3", "vec4"],
+        description: "A single-precision floating-point scalar, and vectors of it. The genType of the functions is any of them.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "double",
+        signatures: &["double", "dvec2", "dvec3", "dvec4"],
+        description: "A double-precision floating-point scalar, and vectors of it.",
+        since: 400,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "mat",
+        signatures: &["mat2", "mat3", "mat4"],
+        description: "Square floating-point matrices, in column-major order.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "matNxM",
+        signatures: &["mat2x2", "mat2x3", "mat2x4", "mat3x2", "mat3x3", "mat3x4", "mat4x2", "mat4x3", "mat4x4"],
+        description: "Floating-point matrices of N columns and M rows.",
+        since: 120,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "sampler2D",
+        signatures: &["sampler2D", "isampler2D", "usampler2D"],
+        description: "A handle to a 2D texture to be used with the texture functions, of floating-point texels, or signed and unsigned integer texels since GLSL 1.30.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+    Entry {
+        name: "samplerCube",
+        signatures: &["samplerCube"],
+        description: "A handle to a cube mapped texture, sampled with a direction vector.",
+        since: 110,
+        removed: None,
+        plot: None,
+    },
+];
@@ -0,0 +1,223 @@
+use eframe::egui;
+
+use crate::renderer::{Shader, Stepper};
+
+#[derive(Debug, Default)]
+pub struct StepThrough {
+    /// The selected frame of the call stack, from the innermost.
+    frame: usize,
+    /// The last shown step, to scroll the source to the current line on change.
+    shown: Option<usize>,
+}
+
+impl StepThrough {
+    pub fn show(&mut self, ui: &mut egui::Ui, stepper: &mut Stepper, shader: Option<&Shader>) {
+        ui.collapsing("👣 Step-through", |ui| {
+            ui.label("Interpret the shader on the CPU for a clicked pixel and step through it.");
+
+            ui.checkbox(
+                &mut stepper.enabled,
+                "Enabled, click the canvas to pick a pixel",
+            );
+
+            if !stepper.enabled {
+                return;
+            }
+
+            match stepper.pixel() {
+                Some(pixel) => {
+                    ui.horizontal(|ui| {
+                        ui.strong("gl_FragCoord");
+                        ui.code(format!("{:.1}, {:.1}", pixel.x + 0.5, pixel.y + 0.5));
+                    });
+                }
+                None => {
+                    ui.label(
+                        egui::RichText::new("Click the canvas to debug a pixel")
+                            .italics()
+                            .weak(),
+                    );
+                }
+            }
+
+            if let Some(error) = stepper.error() {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            let Some(trace) = stepper.trace() else {
+                return;
+            };
+            let (count, truncated) = (trace.steps.len(), trace.truncated);
+
+            ui.horizontal_wrapped(|ui| {
+                if ui.button("⏮").on_hover_text("Restart").clicked() {
+                    stepper.restart();
+                }
+                if ui.button("⏴").on_hover_text("Step back").clicked() {
+                    stepper.step_back();
+                }
+                if ui.button("⤵").on_hover_text("Step into").clicked() {
+                    stepper.step_into();
+                }
+                if ui.button("⏩").on_hover_text("Step over").clicked() {
+                    stepper.step_over();
+                }
+                if ui.button("⤴").on_hover_text("Step out").clicked() {
+                    stepper.step_out();
+                }
+                if ui
+                    .button("⏵")
+                    .on_hover_text("Continue to the next breakpoint")
+                    .clicked()
+                {
+                    stepper.resume();
+                }
+            });
+
+            ui.label(format!(
+                "Step {} of {count}{}",
+                (stepper.position() + 1).min(count),
+                if truncated { " (truncated)" } else { "" }
+            ));
+
+            let changed = self.shown != Some(stepper.position());
+            self.shown = Some(stepper.position());
+            if changed {
+                self.frame = 0;
+            }
+
+            if let Some(shader) = shader {
+                Self::source(ui, stepper, shader, changed);
+            }
+
+            match stepper.step() {
+                Some(step) => {
+                    ui.strong("Call stack");
+                    for (index, frame) in step.frames.iter().rev().enumerate() {
+                        if ui
+                            .selectable_label(
+                                self.frame == index,
+                                format!("{}() at line {}", frame.function, frame.line),
+                            )
+                            .clicked()
+                        {
+                            self.frame = index;
+                        }
+                    }
+
+                    if let Some(frame) = step.frames.iter().rev().nth(self.frame) {
+                        for current in &frame.loops {
+                            ui.label(format!(
+                                "Loop at line {}, iteration {}",
+                                current.line, current.iteration
+                            ));
+                        }
+
+                        egui::Grid::new("locals")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (name, value) in &frame.locals {
+                                    ui.strong(name);
+                                    ui.code(value.to_string());
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                }
+                None => {
+                    ui.label(egui::RichText::new("Execution finished").italics());
+                }
+            }
+
+            ui.separator();
+
+            Self::comparison(ui, stepper);
+        });
+    }
+
+    /// Show the source with the current line highlighted, and toggleable breakpoints.
+    fn source(ui: &mut egui::Ui, stepper: &mut Stepper, shader: &Shader, scroll: bool) {
+        let current = stepper.step().map(|step| step.line());
+
+        egui::ScrollArea::vertical()
+            .id_source("step-source")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                for (number, line) in shader.source().lines().enumerate() {
+                    let number = number + 1;
+
+                    ui.horizontal(|ui| {
+                        let breakpoint = stepper.breakpoints.contains(&number);
+                        let marker = egui::RichText::new(if breakpoint { "●" } else { "○" });
+
+                        if ui
+                            .selectable_label(
+                                false,
+                                if breakpoint {
+                                    marker.color(ui.visuals().error_fg_color)
+                                } else {
+                                    marker.weak()
+                                },
+                            )
+                            .on_hover_text("Toggle breakpoint")
+                            .clicked()
+                        {
+                            if breakpoint {
+                                stepper.breakpoints.remove(&number);
+                            } else {
+                                stepper.breakpoints.insert(number);
+                            }
+                        }
+
+                        let response = ui.selectable_label(
+                            current == Some(number),
+                            egui::RichText::new(format!("{number:>4} {line}")).monospace(),
+                        );
+                        if scroll && current == Some(number) {
+                            response.scroll_to_me(Some(egui::Align::Center));
+                        }
+                    });
+                }
+            });
+    }
+
+    /// Cross-check the interpreted color against the rendered one.
+    fn comparison(ui: &mut egui::Ui, stepper: &Stepper) {
+        let Some(trace) = stepper.trace() else {
+            return;
+        };
+
+        egui::Grid::new("comparison")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("CPU");
+                match trace.output {
+                    _ if trace.discarded => ui.code("(discarded)"),
+                    Some(output) => ui.code(format!("{output:.04?}")),
+                    None => ui.code("(none)"),
+                };
+                ui.end_row();
+
+                if let Some(rendered) = stepper.rendered() {
+                    ui.strong("GPU");
+                    ui.code(format!("{rendered:.04?}"));
+                    ui.end_row();
+                }
+
+                if let Some(difference) = stepper.difference() {
+                    ui.strong("Difference");
+                    if difference <= Stepper::TOLERANCE {
+                        ui.label(format!("✔ {difference:.04}"));
+                    } else {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("⚠ {difference:.04}, the interpretation diverges"),
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,44 @@
+use eframe::egui;
+
+use crate::{renderer::Errors, Renderer};
+
+#[derive(Debug, Default)]
+pub struct Tabs;
+
+impl Tabs {
+    /// Show the tabs, with `errors` the errors of the active one.
+    pub fn show(&self, ctx: &egui::Context, renderer: &mut Renderer, errors: &mut Errors) {
+        let (mut selected, mut closed, mut opened) = (None, None, false);
+
+        egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (index, title) in renderer.tabs().into_iter().enumerate() {
+                    let response = ui
+                        .selectable_label(index == renderer.active(), title)
+                        .on_hover_text("Middle-click to close the tab");
+
+                    if response.clicked() {
+                        selected = Some(index);
+                    }
+                    if response.middle_clicked() || ui.small_button("✖").clicked() {
+                        closed = Some(index);
+                    }
+
+                    ui.separator();
+                }
+
+                opened = ui.button("➕").on_hover_text("Open a new tab").clicked();
+            });
+        });
+
+        if let Some(index) = selected {
+            renderer.select(index, errors);
+        }
+        if let Some(index) = closed {
+            renderer.close(index, errors);
+        }
+        if opened {
+            renderer.open(errors);
+        }
+    }
+}
@@ -1,13 +1,29 @@
 use eframe::egui;
 use strum::IntoEnumIterator;
 
-use crate::{Renderer, UniformStyle};
+use super::{Inspector, Performance, Reference, StepThrough};
+use crate::{
+    canvas::View,
+    midi::{Curve, Midi},
+    renderer::{
+        Alpha, Channel, Discovery, Display, Encoding, Feed, Format, Heatmap, Mode, Navigation,
+        Output, PixelFormat, Plotter, Receiver, Scan, Tonemapper,
+    },
+    Error, Renderer, UniformStyle,
+};
 
 #[derive(Debug, Default)]
-pub struct Tools;
+pub struct Tools {
+    pub inspector: Inspector,
+    pub performance: Performance,
+    step_through: StepThrough,
+    reference: Reference,
+}
 
 impl Tools {
-    pub fn show(&mut self, ctx: &egui::Context, renderer: &mut Renderer) {
+    pub fn show(&mut self, ctx: &egui::Context, renderer: &mut Renderer) -> Option<Error> {
+        let mut error = None;
+
         egui::SidePanel::left("sidebar").show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
                 ui.vertical_centered(|ui| {
@@ -50,42 +66,413 @@ impl Tools {
                                 });
                             });
 
+                            let mut threaded = renderer.worker.is_some();
+                            if ui
+                                .checkbox(&mut threaded, "Render on a dedicated thread")
+                                .on_hover_text(
+                                    "Keep the interface responsive with slow shaders, \
+                                    compiled and rendered on a context shared with the interface.",
+                                )
+                                .changed()
+                            {
+                                if let Err(err) = renderer.set_threaded(threaded) {
+                                    error = Some(err);
+                                }
+                            }
+
                             ui.separator();
 
-                            ui.label("Newtek NDI® Source");
+                            ui.label("Newtek NDI® Outputs");
+
+                            let mut removed = None;
+                            for (index, output) in renderer.outputs.iter_mut().enumerate() {
+                                ui.group(|ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.add_enabled(
+                                            !output.is_running(),
+                                            egui::TextEdit::singleline(&mut output.name)
+                                                .hint_text("Source name")
+                                                .desired_width(ui.available_width() - 24.0),
+                                        );
+                                        if ui.small_button("✖").clicked() {
+                                            removed = Some(index);
+                                        }
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.strong("Size");
+
+                                        let mut follow = output.size.is_none();
+                                        if ui.checkbox(&mut follow, "Texture size").changed() {
+                                            output.size = (!follow).then_some(renderer.size);
+                                        }
+
+                                        if let Some(size) = &mut output.size {
+                                            ui.add(
+                                                egui::DragValue::new(&mut size.x)
+                                                    .clamp_range(16..=7680)
+                                                    .suffix(" px"),
+                                            );
+                                            ui.label("x");
+                                            ui.add(
+                                                egui::DragValue::new(&mut size.y)
+                                                    .clamp_range(16..=4320)
+                                                    .suffix(" px"),
+                                            );
+                                        }
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.strong("Framerate");
+                                        ui.add(
+                                            egui::DragValue::new(&mut output.framerate)
+                                                .clamp_range(1..=240)
+                                                .suffix(" fps"),
+                                        );
+
+                                        egui::ComboBox::from_id_source(("feed", index))
+                                            .selected_text(output.feed.as_ref())
+                                            .show_ui(ui, |ui| {
+                                                for feed in Feed::iter() {
+                                                    ui.selectable_value(
+                                                        &mut output.feed,
+                                                        feed,
+                                                        feed.as_ref(),
+                                                    );
+                                                }
+                                            });
+                                    });
+
+                                    ui.horizontal(|ui| {
+                                        ui.strong("Pixels");
+
+                                        egui::ComboBox::from_id_source(("format", index))
+                                            .width(64.0)
+                                            .selected_text(output.format.as_ref())
+                                            .show_ui(ui, |ui| {
+                                                for format in PixelFormat::iter() {
+                                                    ui.selectable_value(
+                                                        &mut output.format,
+                                                        format,
+                                                        format.as_ref(),
+                                                    );
+                                                }
+                                            });
+
+                                        ui.add_enabled_ui(
+                                            output.format != PixelFormat::Uyvy,
+                                            |ui| {
+                                                egui::ComboBox::from_id_source(("alpha", index))
+                                                    .width(96.0)
+                                                    .selected_text(output.alpha.as_ref())
+                                                    .show_ui(ui, |ui| {
+                                                        for alpha in Alpha::iter() {
+                                                            ui.selectable_value(
+                                                                &mut output.alpha,
+                                                                alpha,
+                                                                alpha.as_ref(),
+                                                            );
+                                                        }
+                                                    })
+                                                    .response
+                                                    .on_hover_text("The alpha of the frames");
+                                            },
+                                        );
+
+                                        egui::ComboBox::from_id_source(("scan", index))
+                                            .selected_text(output.scan.as_ref())
+                                            .show_ui(ui, |ui| {
+                                                for scan in Scan::iter() {
+                                                    ui.selectable_value(
+                                                        &mut output.scan,
+                                                        scan,
+                                                        scan.as_ref(),
+                                                    );
+                                                }
+                                            });
+                                    });
+
+                                    ui.vertical_centered_justified(|ui| {
+                                        if output.is_running() {
+                                            if ui.button("⏹ Stop").clicked() {
+                                                output.stop();
+                                            }
+                                        } else if ui.button("▶ Start").clicked() {
+                                            if let Err(err) = output.start() {
+                                                error = Some(err);
+                                            }
+                                        }
+                                    });
+                                });
+                            }
+                            if let Some(index) = removed {
+                                renderer.outputs.remove(index);
+                            }
+
+                            ui.vertical_centered_justified(|ui| {
+                                if ui.button("➕ Add output").clicked() {
+                                    let name = format!("glou {}", renderer.outputs.len() + 1);
+
+                                    renderer.outputs.push(Output::new(name));
+                                }
+                            });
+                        });
+                    });
+
+                    ui.collapsing("📡 NDI® Input", |ui| {
+                        ui.label(format!(
+                            "Receive a Newtek NDI® source in the `{}` sampler, along with its `{}` and `{}` in seconds.",
+                            renderer.uniforms.style.format("ndi"),
+                            renderer.uniforms.style.format("ndiResolution"),
+                            renderer.uniforms.style.format("ndiAge"),
+                        ));
+
+                        if let Some(receiver) = &renderer.receiver {
+                            egui::Grid::new("ndi-input").num_columns(2).show(ui, |ui| {
+                                ui.strong("Source");
+                                ui.code(receiver.source().name());
+                                ui.end_row();
+
+                                if let Some((size, age)) = receiver.latest() {
+                                    ui.strong("Resolution");
+                                    ui.code(format!("{}x{}", size.x, size.y));
+                                    ui.end_row();
+
+                                    ui.strong("Age");
+                                    ui.code(format!("{:.0} ms", age * 1000.0));
+                                    ui.end_row();
+                                }
+                            });
+
+                            if ui.button("⏹ Disconnect").clicked() {
+                                renderer.receiver = None;
+                            }
+
+                            return;
+                        }
+
+                        let Some(discovery) = &renderer.discovery else {
+                            if ui.button("🔍 Discover sources").clicked() {
+                                match Discovery::new() {
+                                    Ok(discovery) => renderer.discovery = Some(discovery),
+                                    Err(err) => error = Some(err),
+                                }
+                            }
+
+                            return;
+                        };
+
+                        let sources = discovery.sources();
+                        if sources.is_empty() {
+                            ui.label(egui::RichText::new("Looking for sources..").italics());
+                        }
+
+                        for source in sources {
+                            if ui.button(format!("▶ {}", source.name())).clicked() {
+                                match Receiver::new(source) {
+                                    Ok(receiver) => renderer.receiver = Some(receiver),
+                                    Err(err) => error = Some(err),
+                                }
+                            }
+                        }
+                    });
+
+                    ui.collapsing("🎨 Color", |ui| {
+                        ui.label("Render target precision and mapping of the rendered colors to the display.");
 
+                        ui.horizontal(|ui| {
+                            ui.strong("Format");
+                            for format in Format::iter() {
+                                ui.selectable_value(&mut renderer.format, format, format.as_ref());
+                            }
+                        });
+
+                        let display = &mut renderer.display;
+
+                        egui::Grid::new("encodings").num_columns(2).show(ui, |ui| {
+                            for (label, encoding) in [
+                                ("Shader output", &mut display.source),
+                                ("Canvas", &mut display.canvas),
+                                ("NDI & exports", &mut display.output),
+                            ] {
+                                ui.strong(label);
+                                ui.horizontal(|ui| {
+                                    for variant in Encoding::iter() {
+                                        ui.selectable_value(encoding, variant, variant.as_ref());
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+
+                        ui.add(
+                            egui::Slider::new(&mut display.exposure, -10.0..=10.0)
+                                .text("Exposure")
+                                .suffix(" EV"),
+                        );
+
+                        ui.horizontal(|ui| {
+                            ui.strong("Tonemapper");
+                            for tonemapper in Tonemapper::iter() {
+                                ui.selectable_value(
+                                    &mut display.tonemapper,
+                                    tonemapper,
+                                    tonemapper.as_ref(),
+                                );
+                            }
+                        });
+
+                        ui.checkbox(&mut display.highlight, "Highlight invalid pixels");
+                        if display.highlight {
+                            ui.horizontal_wrapped(|ui| {
+                                for (color, description) in Display::HIGHLIGHTS {
+                                    ui.colored_label(color, "⏹");
+                                    ui.label(description);
+                                }
+                            });
+                        }
+                    });
+
+                    ui.collapsing("🔎 View", |ui| {
+                        ui.label(
+                            "Scroll to zoom the canvas, drag to pan and double-click to reset.",
+                        );
+
+                        let view = &mut renderer.view;
+
+                        ui.horizontal(|ui| {
+                            ui.strong("Zoom");
+
+                            let mut zoom = view.zoom;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut zoom)
+                                        .clamp_range(1.0..=View::MAX_ZOOM)
+                                        .speed(0.1)
+                                        .suffix("x"),
+                                )
+                                .changed()
+                            {
+                                view.zoom_to(zoom);
+                            }
+
+                            if ui.button("⟲ Reset").clicked() {
+                                view.reset();
+                            }
+                        });
+
+                        ui.checkbox(
+                            &mut view.grid,
+                            format!("Pixel grid from {}x", View::GRID_ZOOM),
+                        );
+                    });
+
+                    ui.collapsing("🧭 Navigation", |ui| {
+                        ui.label(
+                            "Drag the canvas to pan, scroll or pinch to zoom and right-drag or twist to rotate, driving the navigation uniforms.",
+                        );
+
+                        let navigation = &mut renderer.navigation;
+
+                        ui.checkbox(&mut navigation.enabled, "Enabled");
+                        ui.checkbox(&mut navigation.inertia, "Inertia");
+
+                        egui::Grid::new("navigation").num_columns(2).show(ui, |ui| {
+                            let style = renderer.uniforms.style;
+
+                            let speed = navigation.zoom * 0.01;
+                            ui.strong(style.format("zoom"));
+                            ui.add(
+                                egui::DragValue::new(&mut navigation.zoom)
+                                    .clamp_range(Navigation::ZOOM.0..=Navigation::ZOOM.1)
+                                    .speed(speed),
+                            );
+                            ui.end_row();
+
+                            ui.strong(style.format("offset"));
                             ui.horizontal(|ui| {
-                                ui.strong("Framerate");
-                                ui.add(
-                                    egui::DragValue::new(&mut renderer.ndi_framerate)
-                                        .clamp_range(24..=240)
-                                        .suffix(" fps"),
+                                let speed = 0.01 / navigation.zoom;
+
+                                ui.add(egui::DragValue::new(&mut navigation.offset[0]).speed(speed));
+                                ui.add(egui::DragValue::new(&mut navigation.offset[1]).speed(speed));
+                            });
+                            ui.end_row();
+
+                            ui.strong(style.format("rotation"));
+                            let mut degrees = navigation.rotation.to_degrees();
+                            if ui
+                                .add(egui::DragValue::new(&mut degrees).speed(1.0).suffix("°"))
+                                .changed()
+                            {
+                                navigation.rotation = degrees.to_radians();
+                            }
+                            ui.end_row();
+                        });
+
+                        if ui.button("⟲ Reset").clicked() {
+                            navigation.reset();
+                        }
+
+                        ui.label("Map the fragment coordinates in the shader with:");
+
+                        let style = renderer.uniforms.style;
+                        let (zoom, offset, rotation, resolution) = (
+                            style.format("zoom"),
+                            style.format("offset"),
+                            style.format("rotation"),
+                            style.format("resolution"),
+                        );
+                        ui.code(format!(
+                            "vec2 p = {offset} + mat2(cos({rotation}), sin({rotation}), -sin({rotation}), cos({rotation}))\n    * (gl_FragCoord.xy - 0.5 * {resolution}) / ({resolution}.y * {zoom});"
+                        ));
+                    });
+
+                    ui.collapsing("♫ Sound", |ui| {
+                        ui.label("Sound shader playback and exporting parameters.");
+
+                        ui.monospace(
+                            renderer
+                                .sound
+                                .as_ref()
+                                .map(|sound| sound.path().display().to_string())
+                                .unwrap_or("(none)".into()),
+                        );
+
+                        if let Some(sound) = &mut renderer.sound {
+                            ui.horizontal(|ui| {
+                                ui.strong("Playback");
+
+                                let response = ui.add(
+                                    egui::DragValue::new(&mut sound.duration)
+                                        .clamp_range(1.0..=600.0)
+                                        .suffix(" s"),
                                 );
+                                if response.drag_released() || response.lost_focus() {
+                                    sound.invalidate();
+                                }
                             });
 
-                            ui.vertical_centered_justified(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.strong("Export");
                                 ui.add(
-                                    egui::TextEdit::singleline(&mut renderer.ndi_name)
-                                        .hint_text("Source name"),
+                                    egui::DragValue::new(&mut sound.length)
+                                        .clamp_range(1.0..=3600.0)
+                                        .suffix(" s"),
                                 );
+                            });
 
-                                match &renderer.ndi {
-                                    Some(_ndi) => {
-                                        if ui.button("⏹ Stop").clicked() {
-                                            renderer.ndi = None;
-                                        }
-                                    }
-                                    None => {
-                                        if ui.button("▶ Start").clicked() {
-                                            renderer.ndi = Some(
-                                                nndi::send::Send::new(&renderer.ndi_name, None)
-                                                    .expect("Couldn't initialize NDI sender"),
-                                            );
-                                        }
+                            ui.vertical_centered_justified(|ui| {
+                                if ui.button("💾 Export WAV..").clicked() {
+                                    if let Some(path) = rfd::FileDialog::new()
+                                        .set_title("Export sound shader")
+                                        .add_filter("WAV", &["wav"])
+                                        .save_file()
+                                    {
+                                        sound.request_export(path);
                                     }
                                 }
                             });
-                        });
+                        }
                     });
 
                     egui::CollapsingHeader::new("⚙ Uniforms")
@@ -102,30 +489,437 @@ impl Tools {
                                     );
                                 }
 
-                                for (name, value) in renderer.uniforms.to_iter() {
+                                for (name, value) in renderer.uniforms.builtins() {
                                     ui.horizontal(|ui| {
                                         ui.strong(name);
                                         ui.code(format!("{:.02?}", value));
                                     });
                                 }
 
+                                if !renderer.uniforms.parameters.is_empty() {
+                                    ui.separator();
+                                }
+
+                                for (name, value) in renderer.uniforms.parameters.iter_mut() {
+                                    let learning = renderer.midi.learning.as_ref() == Some(name);
+
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .selectable_label(learning, "🎛")
+                                            .on_hover_text("MIDI learn, click and turn a knob")
+                                            .clicked()
+                                        {
+                                            renderer.midi.learning =
+                                                (!learning).then(|| name.clone());
+                                        }
+
+                                        ui.strong(name);
+                                        ui.add(egui::DragValue::new(value).speed(0.01));
+                                    });
+
+                                    let mut unmap = false;
+                                    let mut changed = false;
+
+                                    if let Some(mapping) = renderer
+                                        .midi
+                                        .mappings
+                                        .iter_mut()
+                                        .find(|mapping| &mapping.uniform == name)
+                                    {
+                                        ui.horizontal(|ui| {
+                                            ui.weak(format!(
+                                                "CC{} ch{}",
+                                                mapping.controller,
+                                                mapping.channel + 1
+                                            ));
+
+                                            changed |= ui
+                                                .add(
+                                                    egui::DragValue::new(&mut mapping.min)
+                                                        .speed(0.01),
+                                                )
+                                                .changed();
+                                            ui.label("to");
+                                            changed |= ui
+                                                .add(
+                                                    egui::DragValue::new(&mut mapping.max)
+                                                        .speed(0.01),
+                                                )
+                                                .changed();
+
+                                            egui::ComboBox::from_id_source(("curve", name))
+                                                .selected_text(mapping.curve.as_ref())
+                                                .show_ui(ui, |ui| {
+                                                    for curve in Curve::iter() {
+                                                        changed |= ui
+                                                            .selectable_value(
+                                                                &mut mapping.curve,
+                                                                curve,
+                                                                curve.as_ref(),
+                                                            )
+                                                            .changed();
+                                                    }
+                                                });
+
+                                            unmap = ui.small_button("✖").clicked();
+                                        });
+                                    }
+
+                                    if unmap {
+                                        error = renderer.midi.unmap(name).err();
+                                    } else if changed {
+                                        error = renderer.midi.save().err();
+                                    }
+                                }
+
                                 ui.separator();
 
                                 if ui.button("⏳ Reset time").clicked() {
                                     renderer.uniforms.reset_time();
                                 }
+
+                                if let Some(audio) = &renderer.audio {
+                                    ui.label(format!(
+                                        "Audio: {}",
+                                        audio
+                                            .path()
+                                            .file_name()
+                                            .unwrap_or_default()
+                                            .to_string_lossy()
+                                    ));
+
+                                    let mut time = renderer.uniforms.time();
+                                    if ui
+                                        .add(
+                                            egui::Slider::new(&mut time, 0.0..=audio.duration())
+                                                .suffix(" s"),
+                                        )
+                                        .changed()
+                                    {
+                                        renderer.uniforms.seek(time);
+                                    }
+                                }
+
+                                ui.separator();
+
+                                ui.label("MIDI input");
+
+                                egui::ComboBox::from_id_source("midi")
+                                    .selected_text(renderer.midi.port().unwrap_or("(none)"))
+                                    .show_ui(ui, |ui| match Midi::ports() {
+                                        Ok(ports) => {
+                                            for port in ports {
+                                                if ui.selectable_label(false, &port).clicked() {
+                                                    error = renderer.midi.connect(&port).err();
+                                                }
+                                            }
+
+                                            #[cfg(unix)]
+                                            if ui.selectable_label(false, "Virtual port").clicked()
+                                            {
+                                                error = renderer.midi.create_virtual().err();
+                                            }
+                                        }
+                                        Err(err) => error = Some(err),
+                                    });
+
+                                if renderer.midi.port().is_some()
+                                    && ui.button("⏏ Disconnect").clicked()
+                                {
+                                    renderer.midi.disconnect();
+                                }
+                            });
+                        });
+
+                    ui.collapsing("🐞 Debug", |ui| {
+                        ui.label(
+                            "Visualize the value of an expression after a line of the shader.",
+                        );
+
+                        let debugger = &mut renderer.debugger;
+
+                        ui.checkbox(&mut debugger.enabled, "Enabled");
+
+                        if let Some(shader) = &renderer.shader {
+                            egui::ScrollArea::vertical()
+                                .id_source("debug-source")
+                                .max_height(200.0)
+                                .show(ui, |ui| {
+                                    for (number, line) in shader.source().lines().enumerate() {
+                                        let number = number + 1;
+
+                                        if ui
+                                            .selectable_label(
+                                                debugger.line == number,
+                                                egui::RichText::new(format!("{number:>4} {line}"))
+                                                    .monospace(),
+                                            )
+                                            .clicked()
+                                        {
+                                            debugger.line = number;
+                                        }
+                                    }
+                                });
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.strong("After line");
+                            ui.add(egui::DragValue::new(&mut debugger.line).clamp_range(1..=99999));
+                        });
+
+                        ui.vertical_centered_justified(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut debugger.expression)
+                                    .code_editor()
+                                    .hint_text("Expression, e.g. `uv`"),
+                            );
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.strong("Value");
+                            match debugger.value {
+                                Some(value) => ui.code(format!("{value:.04?}")),
+                                None => ui.weak("(hover the canvas)"),
+                            };
+                        });
+
+                        ui.separator();
+
+                        ui.checkbox(&mut debugger.visualize, "False-color visualization");
+
+                        ui.add_enabled_ui(debugger.visualize, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.strong("Channel");
+                                for channel in Channel::iter() {
+                                    ui.selectable_value(
+                                        &mut debugger.channel,
+                                        channel,
+                                        channel.as_ref(),
+                                    );
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.strong("Range");
+                                ui.add(egui::DragValue::new(&mut debugger.range.0).speed(0.01));
+                                ui.label("to");
+                                ui.add(egui::DragValue::new(&mut debugger.range.1).speed(0.01));
+                            });
+                        });
+                    });
+
+                    self.step_through
+                        .show(ui, &mut renderer.stepper, renderer.shader.as_ref());
+
+                    ui.collapsing("🔥 Heatmap", |ui| {
+                        ui.label("Overlay the number of loop iterations run by each pixel.");
+
+                        let heatmap = &mut renderer.heatmap;
+
+                        ui.checkbox(&mut heatmap.enabled, "Enabled");
+
+                        if !heatmap.enabled {
+                            return;
+                        }
+
+                        if heatmap.loops().is_empty() {
+                            ui.label(egui::RichText::new("The shader has no loops").italics());
+                        }
+
+                        let loop_label = |only: Option<usize>| match only {
+                            Some(line) => format!("Loop at line {line}"),
+                            None => "All loops".to_string(),
+                        };
+                        egui::ComboBox::from_label("Counted")
+                            .selected_text(loop_label(heatmap.only))
+                            .show_ui(ui, |ui| {
+                                let loops = heatmap.loops().to_vec();
+
+                                ui.selectable_value(&mut heatmap.only, None, loop_label(None));
+                                for line in loops {
+                                    ui.selectable_value(
+                                        &mut heatmap.only,
+                                        Some(line),
+                                        loop_label(Some(line)),
+                                    );
+                                }
+                            });
+
+                        ui.add(egui::Slider::new(&mut heatmap.opacity, 0.0..=1.0).text("Opacity"));
+
+                        ui.checkbox(&mut heatmap.automatic, "Automatic range");
+                        ui.add_enabled_ui(!heatmap.automatic, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.strong("Range");
+                                ui.add(egui::DragValue::new(&mut heatmap.range.0).speed(1.0));
+                                ui.label("to");
+                                ui.add(egui::DragValue::new(&mut heatmap.range.1).speed(1.0));
                             });
                         });
 
-                    ui.collapsing("📖 Reference", |ui| {
-                        ui.label("Some documentation about the GLSL methods and types.");
+                        // The legend of the colormap, from the lower to the upper end of the range
+                        let (low, high) = heatmap.range();
+                        let (rect, _) = ui.allocate_exact_size(
+                            egui::vec2(ui.available_width(), 16.0),
+                            egui::Sense::hover(),
+                        );
+                        let steps = 64;
+                        for step in 0..steps {
+                            let x = step as f32 / steps as f32;
+                            let width = rect.width() / steps as f32;
+
+                            ui.painter().rect_filled(
+                                egui::Rect::from_min_size(
+                                    rect.min + egui::vec2(x * rect.width(), 0.0),
+                                    egui::vec2(width + 0.5, rect.height()),
+                                ),
+                                0.0,
+                                Heatmap::color(x + 0.5 / steps as f32),
+                            );
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{low:.0}"));
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.label(format!("{high:.0}"));
+                                },
+                            );
+                        });
+
+                        egui::Grid::new("heatmap").num_columns(2).show(ui, |ui| {
+                            if let Some((min, max)) = heatmap.extent() {
+                                ui.strong("Minimum");
+                                ui.code(format!("{min:.0}"));
+                                ui.end_row();
+
+                                ui.strong("Maximum");
+                                ui.code(format!("{max:.0}"));
+                                ui.end_row();
+                            }
+
+                            ui.strong("Hovered");
+                            match heatmap.value() {
+                                Some(value) => ui.code(format!("{value:.0}")),
+                                None => ui.weak("(hover the canvas)"),
+                            };
+                            ui.end_row();
+                        });
                     });
 
+                    ui.collapsing("⚖ Compare", |ui| {
+                        ui.label("Compare the shader against another one, rendered with the same uniforms.");
+
+                        let comparison = &mut renderer.comparison;
+
+                        let Some(shader) = &comparison.shader else {
+                            ui.label(
+                                egui::RichText::new("Load a comparison shader from the File menu")
+                                    .italics(),
+                            );
+                            return;
+                        };
+                        ui.code(shader.path().display().to_string());
+
+                        ui.horizontal(|ui| {
+                            for mode in Mode::iter() {
+                                ui.selectable_value(&mut comparison.mode, mode, mode.as_ref());
+                            }
+                        });
+
+                        match comparison.mode {
+                            Mode::Split => {
+                                ui.add(
+                                    egui::Slider::new(&mut comparison.split, 0.0..=1.0)
+                                        .text("Split"),
+                                );
+                            }
+                            Mode::SideBySide => (),
+                            Mode::Difference => {
+                                ui.horizontal(|ui| {
+                                    ui.strong("Amplification");
+                                    ui.add(
+                                        egui::DragValue::new(&mut comparison.amplification)
+                                            .clamp_range(1.0..=1000.0)
+                                            .speed(0.5)
+                                            .suffix("×"),
+                                    );
+                                });
+                            }
+                        }
+
+                        egui::Grid::new("comparison").num_columns(2).show(ui, |ui| {
+                            let Some(statistics) = comparison.statistics() else {
+                                return;
+                            };
+
+                            ui.strong("PSNR");
+                            ui.code(match statistics.psnr {
+                                Some(psnr) => format!("{psnr:.2} dB"),
+                                None => "Identical".to_string(),
+                            });
+                            ui.end_row();
+
+                            ui.strong("Max error");
+                            ui.code(format!("{:.6}", statistics.max_error));
+                            ui.end_row();
+                        });
+                    });
+
+                    ui.collapsing("📈 Plotter", |ui| {
+                        ui.label("Plot GLSL expressions of `x` and the time `t`, such as shaping functions.");
+
+                        let plotter = &mut renderer.plotter;
+
+                        ui.checkbox(&mut plotter.open, "Show plot");
+
+                        let mut removed = None;
+                        for (index, (expression, color)) in
+                            plotter.expressions.iter_mut().enumerate()
+                        {
+                            ui.horizontal(|ui| {
+                                ui.color_edit_button_srgba(color);
+                                ui.add(
+                                    egui::TextEdit::singleline(expression)
+                                        .code_editor()
+                                        .hint_text("smoothstep(0.0, 1.0, x)")
+                                        .desired_width(ui.available_width() - 24.0),
+                                );
+                                if ui.small_button("✖").clicked() {
+                                    removed = Some(index);
+                                }
+                            });
+                        }
+                        if let Some(index) = removed {
+                            plotter.expressions.remove(index);
+                        }
+
+                        if ui
+                            .add_enabled(
+                                plotter.expressions.len() < Plotter::MAX,
+                                egui::Button::new("➕ Add expression"),
+                            )
+                            .clicked()
+                        {
+                            plotter.add();
+                        }
+
+                        ui.weak("Drag to pan, scroll to zoom and double-click to reset.");
+                    });
+
+                    self.inspector.show(ui, &mut renderer.inspector);
+
+                    self.performance.show(ui, &mut renderer.timings);
+
+                    self.reference.show(ui);
+
                     ui.separator();
 
                     ui.label("Press <L> to toggle live mode.");
                 });
             });
         });
+
+        error
     }
 }
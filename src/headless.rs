@@ -0,0 +1,117 @@
+use std::{path::PathBuf, rc::Rc};
+
+use glow::HasContext;
+
+use crate::{
+    renderer::{Format, Pool, Shader, Uniforms},
+    AllocGuard, Error,
+};
+
+/// Renders a shader with the `glou` uniform conventions on any OpenGL 3.3 context,
+/// such as a hidden or headless one, to a texture or to memory.
+#[derive(Debug)]
+pub struct Headless {
+    gl: Rc<glow::Context>,
+    shader: Option<Shader>,
+    uniforms: Uniforms,
+    /// The pixel format of the renders.
+    pub format: Format,
+    /// The position of the mouse, in pixels from the bottom-left corner, or the center if `None`.
+    pub mouse: Option<[f32; 2]>,
+}
+
+impl Headless {
+    /// Render on the `gl` context, which must be current on this thread whenever rendering.
+    pub fn new(gl: Rc<glow::Context>) -> Self {
+        Self {
+            gl,
+            shader: None,
+            uniforms: Uniforms::default(),
+            format: Format::default(),
+            mouse: None,
+        }
+    }
+
+    /// Render the in-memory `source` on the `gl` context, compiled right away.
+    pub fn from_source(gl: Rc<glow::Context>, source: impl Into<String>) -> Result<Self, Error> {
+        let mut headless = Self::new(gl);
+        headless.compile(Shader::from_source(source.into()))?;
+
+        Ok(headless)
+    }
+
+    /// Load and compile the shader at `path`, recompiled before rendering whenever the file changes.
+    pub fn load(&mut self, path: impl Into<PathBuf>) -> Result<(), Error> {
+        self.compile(Shader::new(path.into()))
+    }
+
+    /// Compile and render the `shader` from now on, declaring its parameters.
+    fn compile(&mut self, shader: Shader) -> Result<(), Error> {
+        let shader = self.shader.insert(shader);
+
+        shader.rebuild(&self.gl)?;
+        self.uniforms.declare(shader.parameters());
+
+        Ok(())
+    }
+
+    /// The uniforms of the shader, to change their naming style, seek the time or set the parameters.
+    pub fn uniforms(&mut self) -> &mut Uniforms {
+        &mut self.uniforms
+    }
+
+    /// Render the shader to a texture of `width` by `height` pixels in the [`Headless::format`],
+    /// returned to the render target pool once dropped.
+    pub fn render_to_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> Result<AllocGuard<glow::Texture>, Error> {
+        let Some(shader) = &mut self.shader else {
+            return Err(Error::Unloaded);
+        };
+
+        if shader.rebuild(&self.gl)? {
+            self.uniforms.declare(shader.parameters());
+        }
+
+        let size = [width as f32, height as f32];
+        self.uniforms.update(size, self.mouse);
+
+        unsafe {
+            // Each render is a frame of the render target pool
+            Pool::trim(&self.gl);
+
+            shader.render_to_texture(&self.gl, &self.uniforms, size, self.format)
+        }
+    }
+
+    /// Render the shader to memory, as the RGBA rows of `width` by `height` pixels from the top,
+    /// in the type of the [`Headless::format`]: bytes, or native-endian half or single floats.
+    pub fn render_to_buffer(&mut self, width: u32, height: u32) -> Result<Vec<u8>, Error> {
+        let texture = self.render_to_texture(width, height)?;
+
+        let line = width as usize * self.format.bytes();
+        let mut pixels = vec![0; line * height as usize];
+
+        unsafe {
+            let gl = &self.gl;
+
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Pool::framebuffer(*texture));
+            gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            gl.read_pixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                glow::RGBA,
+                self.format.ty(),
+                glow::PixelPackData::Slice(&mut pixels),
+            );
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        }
+
+        // From OpenGL rows from the bottom to rows from the top
+        Ok(pixels.chunks_exact(line).rev().flatten().copied().collect())
+    }
+}
@@ -0,0 +1,37 @@
+//! Shader loading, uniform conventions and rendering of `glou`, along with a [`Headless`] API
+//! to render shaders from other programs and tests, without any window or interface.
+//!
+//! The interface of `glou` and its audio, MIDI and NDI® parts are only built with the `gui` feature,
+//! which also provides the `ShaderView` widget for egui applications.
+
+#[cfg(feature = "gui")]
+pub mod audio;
+#[cfg(feature = "gui")]
+pub mod canvas;
+#[cfg(feature = "gui")]
+pub mod midi;
+
+pub mod renderer;
+pub use renderer::{Format, Levels, Pool, Shader, UniformStyle, Uniforms};
+#[cfg(feature = "gui")]
+pub use renderer::{Renderer, ShaderView};
+
+mod error;
+pub use error::Error;
+
+mod headless;
+pub use headless::Headless;
+
+/// An allocation, freed by the enclosed function when dropped.
+pub type AllocGuard<T> = scopeguard::ScopeGuard<T, Box<dyn FnOnce(T)>>;
+
+#[macro_export]
+macro_rules! guard {
+    ($gl:ident, $alloc:expr, $delete:expr) => {
+        ::scopeguard::guard($alloc, {
+            let $gl = $gl.clone();
+
+            Box::new($delete) as Box<dyn FnOnce(_)>
+        })
+    };
+}
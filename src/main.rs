@@ -1,27 +1,11 @@
 use eframe::egui;
+use raw_window_handle::HasRawDisplayHandle;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
-mod canvas;
-mod gui;
-
-mod renderer;
-use renderer::{Renderer, Shader, UniformStyle};
-
-mod error;
-use error::Error;
-
-type AllocGuard<T> = scopeguard::ScopeGuard<T, Box<dyn FnOnce(T)>>;
+use glou::{audio, canvas, midi, renderer, Error};
+use renderer::{Pool, Renderer, Shader, UniformStyle};
 
-#[macro_export]
-macro_rules! guard {
-    ($gl:ident, $alloc:expr, $delete:expr) => {
-        ::scopeguard::guard($alloc, {
-            let $gl = $gl.clone();
-
-            Box::new($delete) as Box<dyn FnOnce(_)>
-        })
-    };
-}
+mod gui;
 
 fn main() -> Result<(), eframe::Error> {
     // Set-up the log and traces handler
@@ -45,7 +29,19 @@ fn main() -> Result<(), eframe::Error> {
             centered: true,
             ..Default::default()
         },
-        Box::new(|_| Box::<App>::default()),
+        Box::new(|cc| {
+            let mut app = App::default();
+            app.renderer.connection = Some(cc.raw_display_handle().into());
+
+            if let Some(session) = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            {
+                app.renderer.restore(session);
+            }
+
+            Box::new(app)
+        }),
     )?;
 
     Ok(())
@@ -66,11 +62,24 @@ impl eframe::App for App {
             .gl()
             .expect("Cannot get reference to the underlying `glow` context");
 
+        self.renderer.timings.begin_frame(gl);
+
         self.gui.show(ctx, &mut self.renderer);
 
         if let Some(shader) = &mut self.renderer.shader {
-            match shader.rebuild(gl) {
-                Ok(success) if success => self.gui.clear_error(),
+            let rebuilt = match &mut self.renderer.worker {
+                // The worker compiles the shader on its own thread, it's only read here
+                Some(worker) => shader.reload().and(worker.poll(gl)),
+                None => shader
+                    .rebuild(gl)
+                    .map(|rebuilt| rebuilt.then(|| shader.parameters().to_vec())),
+            };
+
+            match rebuilt {
+                Ok(Some(parameters)) => {
+                    self.renderer.uniforms.declare(&parameters);
+                    self.gui.clear_error();
+                }
                 Err(err) => {
                     tracing::warn!("An error occured while compiling shader: {err}");
 
@@ -80,12 +89,83 @@ impl eframe::App for App {
             }
         }
 
+        // Without a comparison shader, there's no error left to report
+        let compared = match &mut self.renderer.comparison.shader {
+            Some(shader) => shader.rebuild(gl),
+            None => Ok(true),
+        };
+        self.gui.report("comparison shader", compared);
+
+        let debugged = self
+            .renderer
+            .debugger
+            .update(gl, self.renderer.shader.as_ref());
+        self.gui.report("debugged variant", debugged);
+
+        let stepped = self.renderer.stepper.update(self.renderer.shader.as_ref());
+        self.gui.report("shader interpretation", stepped);
+
+        let counted = self
+            .renderer
+            .heatmap
+            .update(gl, self.renderer.shader.as_ref());
+        self.gui.report("heatmap variant", counted);
+
+        if self.renderer.plotter.open {
+            self.renderer.plotter.update(gl);
+        }
+
+        if let Some(sound) = &mut self.renderer.sound {
+            if let Err(err) = sound.update(gl, &self.renderer.uniforms) {
+                tracing::warn!("An error occured while updating sound shader: {err}");
+
+                self.gui.set_error(err);
+            }
+        }
+
+        if let Err(err) = self
+            .renderer
+            .midi
+            .attach(self.renderer.shader.as_ref().map(Shader::path))
+            .and_then(|()| self.renderer.midi.poll(&mut self.renderer.uniforms))
+        {
+            tracing::warn!("An error occured while handling MIDI input: {err}");
+
+            self.gui.set_error(err);
+        }
+
+        if let Err(err) = self.renderer.receive(gl) {
+            tracing::warn!("An error occured while receiving NDI® source: {err}");
+
+            self.gui.set_error(err);
+        }
+
         egui::CentralPanel::default()
             .frame(egui::Frame::canvas(&ctx.style()))
             .show(ctx, |ui| {
-                self.renderer.render_to_canvas(gl, ui).paint();
+                let canvas = self.renderer.render_to_canvas(gl, ui);
+
+                self.gui.hover(canvas.response(), &self.renderer);
+                canvas.paint();
             });
 
-        self.renderer.send(gl);
+        if self.renderer.plotter.open {
+            self.renderer
+                .plotter
+                .show(gl, ctx, self.renderer.uniforms.time());
+        }
+
+        if let Err(err) = self.renderer.send(gl) {
+            tracing::warn!("An error occured while sending NDI® source: {err}");
+
+            self.gui.set_error(err);
+        }
+        self.renderer.timings.end_frame();
+
+        unsafe { Pool::trim(gl) };
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.renderer.session());
     }
 }
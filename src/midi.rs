@@ -0,0 +1,365 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter};
+
+use crate::{Error, Uniforms};
+
+/// The client name under which `glou` registers to the MIDI subsystem.
+const CLIENT: &str = "glou";
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr, Serialize, Deserialize)]
+pub enum Curve {
+    #[default]
+    Linear,
+    Exponential,
+    Logarithmic,
+
+    #[strum(serialize = "S-Curve")]
+    Smooth,
+}
+
+impl Curve {
+    /// Shape a normalized `[0; 1]` controller value along the curve.
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Self::Linear => x,
+            Self::Exponential => (64f32.powf(x) - 1.0) / 63.0,
+            Self::Logarithmic => (1.0 + 63.0 * x).log(64.0),
+            Self::Smooth => x * x * (3.0 - 2.0 * x),
+        }
+    }
+}
+
+/// A binding from a MIDI _Control Change_ to a shader parameter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mapping {
+    pub uniform: String,
+    pub channel: u8,
+    pub controller: u8,
+    pub min: f32,
+    pub max: f32,
+    pub curve: Curve,
+}
+
+impl Mapping {
+    pub fn new(uniform: String, channel: u8, controller: u8) -> Self {
+        Self {
+            uniform,
+            channel,
+            controller,
+            min: 0.0,
+            max: 1.0,
+            curve: Default::default(),
+        }
+    }
+
+    pub fn value(&self, raw: u8) -> f32 {
+        let x = raw.min(127) as f32 / 127.0;
+
+        self.min + (self.max - self.min) * self.curve.apply(x)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ControlChange {
+    channel: u8,
+    controller: u8,
+    value: u8,
+}
+
+impl ControlChange {
+    fn parse(message: &[u8]) -> Option<Self> {
+        match message {
+            [status, controller, value] if status & 0xF0 == 0xB0 => Some(Self {
+                channel: status & 0x0F,
+                controller: *controller,
+                value: *value,
+            }),
+            _ => None,
+        }
+    }
+}
+
+struct Connection {
+    port: String,
+    receiver: mpsc::Receiver<ControlChange>,
+    _inner: midir::MidiInputConnection<()>,
+}
+
+#[derive(Default)]
+pub struct Midi {
+    connection: Option<Connection>,
+    path: Option<PathBuf>,
+
+    pub mappings: Vec<Mapping>,
+    pub learning: Option<String>,
+}
+
+impl std::fmt::Debug for Midi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Midi")
+            .field("port", &self.port())
+            .field("path", &self.path)
+            .field("mappings", &self.mappings)
+            .field("learning", &self.learning)
+            .finish()
+    }
+}
+
+impl Midi {
+    /// List the names of the MIDI input ports available on the system.
+    pub fn ports() -> Result<Vec<String>, Error> {
+        let input = midir::MidiInput::new(CLIENT).map_err(|err| Error::Midi(err.to_string()))?;
+
+        Ok(input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .collect())
+    }
+
+    /// The name of the currently connected port, if any.
+    pub fn port(&self) -> Option<&str> {
+        self.connection
+            .as_ref()
+            .map(|connection| connection.port.as_str())
+    }
+
+    pub fn connect(&mut self, name: &str) -> Result<(), Error> {
+        self.disconnect();
+
+        let input = midir::MidiInput::new(CLIENT).map_err(|err| Error::Midi(err.to_string()))?;
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).ok().as_deref() == Some(name))
+            .ok_or_else(|| Error::Midi(format!("No such MIDI input port `{name}`")))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let inner = input
+            .connect(
+                &port,
+                CLIENT,
+                move |_, message, _| {
+                    if let Some(cc) = ControlChange::parse(message) {
+                        let _ = sender.send(cc);
+                    }
+                },
+                (),
+            )
+            .map_err(|err| Error::Midi(err.to_string()))?;
+
+        tracing::info!("Connected to MIDI input port `{name}`");
+
+        self.connection = Some(Connection {
+            port: name.to_string(),
+            receiver,
+            _inner: inner,
+        });
+
+        Ok(())
+    }
+
+    /// Create a virtual input port other applications can connect to.
+    #[cfg(unix)]
+    pub fn create_virtual(&mut self) -> Result<(), Error> {
+        use midir::os::unix::VirtualInput;
+
+        self.disconnect();
+
+        let input = midir::MidiInput::new(CLIENT).map_err(|err| Error::Midi(err.to_string()))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let inner = input
+            .create_virtual(
+                CLIENT,
+                move |_, message, _| {
+                    if let Some(cc) = ControlChange::parse(message) {
+                        let _ = sender.send(cc);
+                    }
+                },
+                (),
+            )
+            .map_err(|err| Error::Midi(err.to_string()))?;
+
+        tracing::info!("Created virtual MIDI input port `{CLIENT}`");
+
+        self.connection = Some(Connection {
+            port: format!("{CLIENT} (virtual)"),
+            receiver,
+            _inner: inner,
+        });
+
+        Ok(())
+    }
+
+    pub fn disconnect(&mut self) {
+        self.connection = None;
+    }
+
+    /// Load the mappings associated with the shader at `path`, if they differ from the current ones.
+    pub fn attach(&mut self, path: Option<&Path>) -> Result<(), Error> {
+        let path = path.map(Self::mappings_path);
+
+        if path == self.path {
+            return Ok(());
+        }
+
+        self.path = path;
+        self.learning = None;
+        self.mappings.clear();
+
+        match &self.path {
+            Some(path) if path.exists() => {
+                tracing::info!("Loading MIDI mappings from `{}`", path.display());
+
+                self.mappings = ron::from_str(&std::fs::read_to_string(path)?)?;
+
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Save the mappings next to the currently attached shader.
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(path) = &self.path {
+            tracing::info!("Saving MIDI mappings to `{}`", path.display());
+
+            std::fs::write(
+                path,
+                ron::ser::to_string_pretty(&self.mappings, Default::default())?,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Forget the mapping for the provided uniform.
+    pub fn unmap(&mut self, uniform: &str) -> Result<(), Error> {
+        self.mappings.retain(|mapping| mapping.uniform != uniform);
+
+        self.save()
+    }
+
+    /// Process the pending _Control Change_ messages, either binding them when learning or updating the uniforms.
+    pub fn poll(&mut self, uniforms: &mut Uniforms) -> Result<(), Error> {
+        let Some(connection) = &self.connection else {
+            return Ok(());
+        };
+
+        let mut learnt = false;
+
+        for cc in connection.receiver.try_iter() {
+            if let Some(uniform) = self.learning.take() {
+                tracing::info!(
+                    "Mapped CC{} on channel {} to `{uniform}`",
+                    cc.controller,
+                    cc.channel + 1
+                );
+
+                self.mappings.retain(|mapping| mapping.uniform != uniform);
+                self.mappings
+                    .push(Mapping::new(uniform, cc.channel, cc.controller));
+
+                learnt = true;
+            }
+
+            for mapping in self.mappings.iter().filter(|mapping| {
+                mapping.channel == cc.channel && mapping.controller == cc.controller
+            }) {
+                uniforms.set_parameter(&mapping.uniform, mapping.value(cc.value));
+            }
+        }
+
+        if learnt {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    fn mappings_path(path: &Path) -> PathBuf {
+        let mut path = OsString::from(path);
+        path.push(".midi.ron");
+
+        path.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn curves() {
+        for curve in Curve::iter() {
+            assert!(
+                curve.apply(0.0).abs() < 1e-6,
+                "{curve:?} doesn't start at 0"
+            );
+            assert!(
+                (curve.apply(1.0) - 1.0).abs() < 1e-6,
+                "{curve:?} doesn't end at 1"
+            );
+
+            let samples = (0..=16).map(|x| curve.apply(x as f32 / 16.0));
+            let increasing = samples.clone().zip(samples.skip(1)).all(|(a, b)| a < b);
+            assert!(increasing, "{curve:?} isn't increasing");
+        }
+
+        assert_eq!(Curve::Linear.apply(0.25), 0.25);
+        assert!(Curve::Exponential.apply(0.5) < 0.5);
+        assert!(Curve::Logarithmic.apply(0.5) > 0.5);
+        assert_eq!(Curve::Smooth.apply(0.5), 0.5);
+        assert!(Curve::Smooth.apply(0.25) < 0.25);
+    }
+
+    #[test]
+    fn mapping() {
+        let mut mapping = Mapping::new("u_cutoff".into(), 0, 74);
+        assert_eq!(mapping.value(0), 0.0);
+        assert_eq!(mapping.value(127), 1.0);
+
+        mapping.min = -2.0;
+        mapping.max = 6.0;
+        assert_eq!(mapping.value(0), -2.0);
+        assert_eq!(mapping.value(127), 6.0);
+        assert!((mapping.value(64) - (-2.0 + 8.0 * 64.0 / 127.0)).abs() < 1e-5);
+
+        // The data bytes are 7-bit, the larger values are clamped
+        assert_eq!(mapping.value(255), 6.0);
+
+        // Inverted ranges are allowed, to turn a knob the other way
+        mapping.min = 1.0;
+        mapping.max = 0.0;
+        mapping.curve = Curve::Exponential;
+        assert_eq!(mapping.value(0), 1.0);
+        assert!(mapping.value(64) > 0.5);
+    }
+
+    #[test]
+    fn control_change() {
+        let cc = ControlChange::parse(&[0xB3, 74, 100]).expect("Not parsed as a Control Change");
+        assert_eq!((cc.channel, cc.controller, cc.value), (3, 74, 100));
+
+        // A Note On, and a truncated message
+        assert!(ControlChange::parse(&[0x93, 60, 100]).is_none());
+        assert!(ControlChange::parse(&[0xB0, 74]).is_none());
+    }
+
+    #[test]
+    fn mappings_path() {
+        assert_eq!(
+            Midi::mappings_path(Path::new("shaders/wave.frag")),
+            Path::new("shaders/wave.frag.midi.ron")
+        );
+    }
+}
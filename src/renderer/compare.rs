@@ -0,0 +1,230 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+use strum::{AsRefStr, EnumIter};
+
+use super::{pass::Pass, reduce::Reduction, Display, Format, Shader, Uniforms};
+use crate::{AllocGuard, Error};
+
+/// The way the two renders are shown together.
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+pub enum Mode {
+    /// The first render left of a draggable line, the second on the right.
+    #[default]
+    Split,
+    /// The centers of both renders next to each other.
+    #[strum(serialize = "Side by side")]
+    SideBySide,
+    /// The amplified absolute difference of the raw renders.
+    Difference,
+}
+
+/// The composition of the two renders.
+const COMPOSE: &str = r#"
+    #version 330 core
+
+    uniform sampler2D _glou_a;
+    uniform sampler2D _glou_b;
+    uniform sampler2D _glou_a_raw;
+    uniform sampler2D _glou_b_raw;
+    uniform int _glou_mode;
+    uniform float _glou_split;
+    uniform float _glou_amplification;
+
+    out vec4 _glou_output;
+
+    void main() {
+        ivec2 coord = ivec2(gl_FragCoord.xy);
+        ivec2 size = textureSize(_glou_a, 0);
+
+        if (_glou_mode == 2) {
+            vec4 difference = abs(texelFetch(_glou_a_raw, coord, 0) - texelFetch(_glou_b_raw, coord, 0));
+
+            _glou_output = vec4(clamp(difference.rgb * _glou_amplification, 0.0, 1.0), 1.0);
+            return;
+        }
+
+        int split = _glou_mode == 0 ? int(_glou_split * float(size.x)) : size.x / 2;
+        if (coord.x == split) {
+            _glou_output = vec4(1.0);
+            return;
+        }
+
+        // Side by side, each half shows the center of its render
+        ivec2 source = coord;
+        if (_glou_mode == 1) {
+            source.x += coord.x < split ? size.x / 4 : -size.x / 4;
+        }
+
+        _glou_output = coord.x < split ? texelFetch(_glou_a, source, 0) : texelFetch(_glou_b, source, 0);
+    }
+"#;
+
+/// The squared error summed in red, and the maximum error in green and blue,
+/// of the color channels of the two raw renders.
+const DIFFERENCE: &str = r#"
+    uniform sampler2D _glou_b;
+
+    vec4 value(ivec2 coord) {
+        vec3 error = abs(texelFetch(_glou_input, coord, 0).rgb - texelFetch(_glou_b, coord, 0).rgb);
+        float max_error = max(error.r, max(error.g, error.b));
+
+        return vec4(dot(error, error), max_error, max_error, 1.0);
+    }
+"#;
+
+/// The error statistics between the two raw renders.
+#[derive(Debug, Clone, Copy)]
+pub struct Statistics {
+    /// The peak signal-to-noise ratio, in decibels, for a peak of `1.0`,
+    /// undefined for identical renders.
+    pub psnr: Option<f32>,
+    /// The maximum absolute difference of a color channel.
+    pub max_error: f32,
+}
+
+/// Compares the main shader against a second one, rendered with identical uniforms.
+#[derive(Debug)]
+pub struct Comparison {
+    pub shader: Option<Shader>,
+    pub mode: Mode,
+    /// The position of the split line, from `0` on the left to `1` on the right.
+    pub split: f32,
+    /// The factor applied to the difference.
+    pub amplification: f32,
+
+    statistics: Option<Statistics>,
+    /// Whether the split line is being dragged.
+    dragging: bool,
+    pass: Option<Pass>,
+    reduction: Reduction,
+}
+
+impl Default for Comparison {
+    fn default() -> Self {
+        Self {
+            shader: None,
+            mode: Default::default(),
+            split: 0.5,
+            amplification: 10.0,
+            statistics: None,
+            dragging: false,
+            pass: None,
+            reduction: Reduction::new(DIFFERENCE),
+        }
+    }
+}
+
+impl Comparison {
+    /// The distance to the split line from which it can be grabbed, in pixels.
+    const GRAB: f32 = 6.0;
+
+    /// Drag the split line, with `pointer` the position of the pointer in the canvas of `width`, in pixels.
+    /// Returns whether the line is being dragged, taking over the other canvas interactions.
+    pub fn interact(
+        &mut self,
+        response: &egui::Response,
+        pointer: Option<egui::Pos2>,
+        width: f32,
+    ) -> bool {
+        if self.shader.is_none() || self.mode != Mode::Split {
+            self.dragging = false;
+
+            return false;
+        }
+
+        let Some(pointer) = pointer else {
+            return self.dragging;
+        };
+
+        if response.drag_started() {
+            self.dragging = (pointer.x - self.split * width).abs() <= Self::GRAB;
+        }
+        if !response.dragged() {
+            self.dragging = false;
+        }
+
+        if self.dragging {
+            self.split = (pointer.x / width).clamp(0.0, 1.0);
+        }
+
+        self.dragging
+    }
+
+    pub fn statistics(&self) -> Option<Statistics> {
+        self.statistics
+    }
+
+    /// Render the second shader like the raw render `a`, compute the statistics between both
+    /// and compose the displayed `image` of `a` with the one of the second shader, if loaded.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) unsafe fn render(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        uniforms: &Uniforms,
+        size: egui::Vec2,
+        format: Format,
+        display: &mut Display,
+        a: glow::Texture,
+        image: glow::Texture,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        let Some(shader) = &self.shader else {
+            self.statistics = None;
+            self.reduction.reset();
+
+            return Ok(None);
+        };
+
+        let b = shader.render_to_texture(gl, uniforms, size.into(), format)?;
+        let displayed = display.render(gl, *b, size, display.canvas, display.highlight)?;
+        let (b, displayed) = (*b, displayed.as_deref().copied().unwrap_or(*b));
+
+        // The statistics of a previous frame, read back without stalling
+        self.reduction
+            .reduce(gl, size, &[("_glou_input", a), ("_glou_b", b)], |_| ())?;
+        if let Some([squared, max_error, _, count]) = self.reduction.collect(gl) {
+            let mse = squared / (count * 3.0).max(1.0);
+
+            self.statistics = Some(Statistics {
+                psnr: (mse > 0.0).then(|| 10.0 * (1.0 / mse).log10()),
+                max_error,
+            });
+        }
+
+        let pass = match &self.pass {
+            Some(pass) => pass,
+            None => self.pass.insert(Pass::new(gl, COMPOSE)?),
+        };
+
+        pass.render_to_texture(
+            gl,
+            size,
+            Format::Rgba8,
+            &[
+                ("_glou_a", image),
+                ("_glou_b", displayed),
+                ("_glou_a_raw", a),
+                ("_glou_b_raw", b),
+            ],
+            |program| {
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_mode").as_ref(),
+                    self.mode as i32,
+                );
+                gl.uniform_1_f32(
+                    gl.get_uniform_location(program, "_glou_split").as_ref(),
+                    self.split,
+                );
+                gl.uniform_1_f32(
+                    gl.get_uniform_location(program, "_glou_amplification")
+                        .as_ref(),
+                    self.amplification,
+                );
+            },
+        )
+        .map(Some)
+    }
+}
@@ -0,0 +1,193 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+use strum::{AsRefStr, EnumIter};
+
+use super::{
+    instrument, pass::Pass, shader::Program, target, Format, Inspection, Shader, Uniforms,
+};
+use crate::{AllocGuard, Error};
+
+/// The component of the debugged value mapped to colors.
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+pub enum Channel {
+    #[default]
+    X,
+    Y,
+    Z,
+    W,
+}
+
+/// The false-color visualization of a single channel of a floating-point texture.
+const COLORMAP: &str = r#"
+    #version 330 core
+
+    uniform sampler2D _glou_input;
+    uniform int _glou_channel;
+    uniform vec2 _glou_range;
+
+    out vec4 _glou_output;
+
+    vec3 turbo(float x);
+
+    void main() {
+        float value = texelFetch(_glou_input, ivec2(gl_FragCoord.xy), 0)[_glou_channel];
+
+        if (isnan(value) || isinf(value)) {
+            _glou_output = vec4(1.0, 0.0, 1.0, 1.0);
+        } else {
+            _glou_output = vec4(turbo((value - _glou_range.x) / (_glou_range.y - _glou_range.x)), 1.0);
+        }
+    }
+"#;
+
+/// The polynomial approximation of the Turbo colormap, by Google LLC.
+pub(super) const TURBO: &str = r#"
+    vec3 turbo(float x) {
+        const vec4 r4 = vec4(0.13572138, 4.61539260, -42.66032258, 132.13108234);
+        const vec4 g4 = vec4(0.09140261, 2.19418839, 4.84296658, -14.18503333);
+        const vec4 b4 = vec4(0.10667330, 12.64194608, -60.58204836, 110.36276771);
+        const vec2 r2 = vec2(-152.94239396, 59.28637943);
+        const vec2 g2 = vec2(4.27729857, 2.82956604);
+        const vec2 b2 = vec2(-89.90310912, 27.34824973);
+
+        x = clamp(x, 0.0, 1.0);
+        vec4 v4 = vec4(1.0, x, x * x, x * x * x);
+        vec2 v2 = v4.zw * v4.z;
+
+        return vec3(dot(v4, r4) + dot(v2, r2), dot(v4, g4) + dot(v2, g2), dot(v4, b4) + dot(v2, b2));
+    }
+"#;
+
+/// Debugs in-GPU values by compiling a variant of the shader outputting an expression at a chosen line.
+#[derive(Debug)]
+pub struct Debugger {
+    pub enabled: bool,
+    pub line: usize,
+    pub expression: String,
+
+    pub visualize: bool,
+    pub channel: Channel,
+    pub range: (f32, f32),
+
+    /// The value of the expression at the hovered pixel.
+    pub value: Option<[f32; 4]>,
+
+    /// The shader source, line and expression the variant was instrumented from.
+    input: (String, usize, String),
+    variant: Option<Program>,
+    colormap: Option<Pass>,
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            line: 1,
+            expression: String::new(),
+            visualize: false,
+            channel: Default::default(),
+            range: (0.0, 1.0),
+            value: None,
+            input: (String::new(), 0, String::new()),
+            variant: None,
+            colormap: None,
+        }
+    }
+}
+
+impl Debugger {
+    /// Recompile the instrumented variant if the shader or the debugged expression changed,
+    /// returning whether it changed.
+    pub fn update(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        shader: Option<&Shader>,
+    ) -> Result<bool, Error> {
+        let source = match shader {
+            Some(shader) if self.enabled && !self.expression.trim().is_empty() => shader.source(),
+            _ => "",
+        };
+
+        // Don't instrument nor recompile the same variant every frame, even if erroneous
+        let (input, line, expression) = &self.input;
+        if source == input && self.line == *line && &self.expression == expression {
+            return Ok(false);
+        }
+
+        self.input = (source.to_owned(), self.line, self.expression.clone());
+        self.variant = None;
+
+        if !source.is_empty() {
+            let variant = instrument::expression(source, self.line, &self.expression)?;
+
+            self.variant = Some(
+                unsafe { Shader::compile(gl, &variant) }
+                    .map_err(|err| Error::Compile(format!("In debugged variant, {err}")))?,
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// Render the variant, read the value at `pixel` and return the false-color texture if enabled.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
+    pub unsafe fn render(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        uniforms: &Uniforms,
+        size: egui::Vec2,
+        pixel: Option<egui::Pos2>,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        self.value = None;
+
+        let Some(program) = &self.variant else {
+            return Ok(None);
+        };
+
+        let texture = target::render_to_texture(gl, size.into(), Format::Rgba32F, || {
+            Shader::draw(gl, program, uniforms);
+        })?;
+
+        if let Some(pixel) = pixel {
+            self.value = Some(Inspection::read(gl, *texture, size, pixel)?.rgba);
+        }
+
+        if !self.visualize {
+            return Ok(None);
+        }
+
+        let colormap = match &self.colormap {
+            Some(colormap) => colormap,
+            None => self
+                .colormap
+                .insert(Pass::new(gl, &format!("{COLORMAP}{TURBO}"))?),
+        };
+
+        colormap
+            .render_to_texture(
+                gl,
+                size,
+                Format::Rgba8,
+                &[("_glou_input", *texture)],
+                |program| {
+                    gl.uniform_1_i32(
+                        gl.get_uniform_location(program, "_glou_channel").as_ref(),
+                        self.channel as i32,
+                    );
+                    gl.uniform_2_f32(
+                        gl.get_uniform_location(program, "_glou_range").as_ref(),
+                        self.range.0,
+                        self.range.1,
+                    );
+                },
+            )
+            .map(Some)
+    }
+}
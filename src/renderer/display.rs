@@ -0,0 +1,182 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter};
+
+use super::{pass::Pass, Format};
+use crate::{AllocGuard, Error};
+
+/// The mapping of the rendered colors to the displayable range.
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr, Serialize, Deserialize)]
+pub enum Tonemapper {
+    #[default]
+    Clamp,
+    Reinhard,
+    #[strum(serialize = "ACES")]
+    Aces,
+}
+
+/// The transfer function of color values.
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr, Serialize, Deserialize)]
+pub enum Encoding {
+    Linear,
+    #[default]
+    #[strum(serialize = "sRGB")]
+    Srgb,
+}
+
+/// The decoding, exposure, tonemapping, highlighting and encoding of a rendered texture.
+const TONEMAP: &str = r#"
+    #version 330 core
+
+    uniform sampler2D _glou_input;
+    uniform bool _glou_decode;
+    uniform float _glou_exposure;
+    uniform int _glou_tonemapper;
+    uniform bool _glou_highlight;
+    uniform bool _glou_encode;
+
+    out vec4 _glou_output;
+
+    vec3 srgb_to_linear(vec3 c) {
+        return mix(c / 12.92, pow((max(c, 0.0) + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+    }
+
+    vec3 linear_to_srgb(vec3 c) {
+        return mix(c * 12.92, 1.055 * pow(max(c, 0.0), vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c));
+    }
+
+    // Krzysztof Narkowicz's fit of the ACES filmic curve
+    vec3 aces(vec3 x) {
+        return (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14);
+    }
+
+    void main() {
+        vec4 color = texelFetch(_glou_input, ivec2(gl_FragCoord.xy), 0);
+        vec3 linear = _glou_decode ? srgb_to_linear(color.rgb) : color.rgb;
+        vec3 exposed = linear * exp2(_glou_exposure);
+
+        if (_glou_highlight) {
+            if (any(isnan(color))) {
+                _glou_output = vec4(1.0, 0.0, 1.0, 1.0);
+                return;
+            } else if (any(isinf(color))) {
+                _glou_output = vec4(0.0, 1.0, 1.0, 1.0);
+                return;
+            } else if (any(greaterThan(exposed, vec3(1.0)))) {
+                _glou_output = vec4(1.0, 0.0, 0.0, 1.0);
+                return;
+            } else if (any(lessThan(exposed, vec3(0.0)))) {
+                _glou_output = vec4(0.0, 0.0, 1.0, 1.0);
+                return;
+            }
+        }
+
+        vec3 mapped = max(exposed, vec3(0.0));
+        if (_glou_tonemapper == 1) {
+            mapped = mapped / (1.0 + mapped);
+        } else if (_glou_tonemapper == 2) {
+            mapped = aces(mapped);
+        }
+
+        mapped = clamp(mapped, 0.0, 1.0);
+        if (_glou_encode) {
+            mapped = linear_to_srgb(mapped);
+        }
+
+        _glou_output = vec4(mapped, clamp(color.a, 0.0, 1.0));
+    }
+"#;
+
+/// Maps the rendered texture to displayable colors, tonemapping in linear space.
+#[derive(Debug, Default)]
+pub struct Display {
+    /// The encoding of the colors output by the shader.
+    pub source: Encoding,
+    /// The encoding expected by the canvas, `sRGB` for the usual displays.
+    pub canvas: Encoding,
+    /// The encoding of the read back pixels, sent over NDI and exported.
+    pub output: Encoding,
+
+    /// The exposure adjustment, in stops.
+    pub exposure: f32,
+    pub tonemapper: Tonemapper,
+    /// Show the NaN, infinite and out-of-range pixels in magenta, cyan, red (above one) and blue (below zero),
+    /// on the canvas only.
+    pub highlight: bool,
+
+    pass: Option<Pass>,
+}
+
+impl Display {
+    /// The colors of the highlighted pixels, with their description.
+    pub const HIGHLIGHTS: [(egui::Color32, &'static str); 4] = [
+        (egui::Color32::from_rgb(255, 0, 255), "NaN"),
+        (egui::Color32::from_rgb(0, 255, 255), "Infinite"),
+        (egui::Color32::from_rgb(255, 0, 0), "Above 1"),
+        (egui::Color32::from_rgb(0, 0, 255), "Below 0"),
+    ];
+
+    /// Whether mapping the colors to the `target` encoding leaves them untouched, besides clamping.
+    pub fn is_identity(&self, target: Encoding, highlight: bool) -> bool {
+        self.source == target
+            && self.exposure == 0.0
+            && self.tonemapper == Tonemapper::Clamp
+            && !highlight
+    }
+
+    /// Map `texture` of size `size` to displayable colors in the `target` encoding,
+    /// highlighting the invalid pixels if `highlight`, or return `None` if it wouldn't change anything.
+    pub(super) unsafe fn render(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        size: egui::Vec2,
+        target: Encoding,
+        highlight: bool,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        if self.is_identity(target, highlight) {
+            return Ok(None);
+        }
+
+        let pass = match &self.pass {
+            Some(pass) => pass,
+            None => self.pass.insert(Pass::new(gl, TONEMAP)?),
+        };
+
+        pass.render_to_texture(
+            gl,
+            size,
+            Format::Rgba8,
+            &[("_glou_input", texture)],
+            |program| {
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_decode").as_ref(),
+                    (self.source == Encoding::Srgb) as i32,
+                );
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_encode").as_ref(),
+                    (target == Encoding::Srgb) as i32,
+                );
+                gl.uniform_1_f32(
+                    gl.get_uniform_location(program, "_glou_exposure").as_ref(),
+                    self.exposure,
+                );
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_tonemapper")
+                        .as_ref(),
+                    self.tonemapper as i32,
+                );
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_highlight").as_ref(),
+                    highlight as i32,
+                );
+            },
+        )
+        .map(Some)
+    }
+}
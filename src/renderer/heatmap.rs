@@ -0,0 +1,267 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{
+    debug::TURBO, instrument, pass::Pass, reduce::Reduction, shader::Program, target, Format,
+    Shader, Uniforms,
+};
+use crate::{AllocGuard, Error};
+
+/// The overlay of the iteration counts over the rendered image.
+const OVERLAY: &str = r#"
+    #version 330 core
+
+    uniform sampler2D _glou_input;
+    uniform sampler2D _glou_image;
+    uniform vec2 _glou_range;
+    uniform float _glou_opacity;
+
+    out vec4 _glou_output;
+
+    vec3 turbo(float x);
+
+    void main() {
+        ivec2 coord = ivec2(gl_FragCoord.xy);
+        float count = texelFetch(_glou_input, coord, 0).r;
+        vec3 image = texelFetch(_glou_image, coord, 0).rgb;
+
+        // Pixels which didn't run any loop iteration are left untouched
+        float opacity = count > 0.0 ? _glou_opacity : 0.0;
+        float x = (count - _glou_range.x) / max(_glou_range.y - _glou_range.x, 1.0);
+
+        _glou_output = vec4(mix(image, turbo(x), opacity), 1.0);
+    }
+"#;
+
+/// The maximum count in green and the minimum one in blue, with the count at `_glou_pixel`
+/// in alpha and whether the pixel is in the frame in red.
+const EXTENT: &str = r#"
+    uniform ivec2 _glou_pixel;
+
+    vec4 value(ivec2 coord) {
+        float count = texelFetch(_glou_input, coord, 0).r;
+        bool hovered = coord == _glou_pixel;
+
+        return vec4(hovered ? 1.0 : 0.0, count, count, hovered ? count : 0.0);
+    }
+"#;
+
+/// Counts the loop iterations run by each pixel, through a variant of the shader with instrumented loops.
+#[derive(Debug)]
+pub struct Heatmap {
+    pub enabled: bool,
+    /// The line of the only loop to count, or all of them if `None`.
+    pub only: Option<usize>,
+    /// Map the colors from the minimum to the maximum count of the frame, instead of `range`.
+    pub automatic: bool,
+    pub range: (f32, f32),
+    pub opacity: f32,
+
+    /// The lines at which the loops of the shader start.
+    loops: Vec<usize>,
+    /// The minimum and maximum counts of a recent frame.
+    extent: Option<(f32, f32)>,
+    /// The count at the hovered pixel.
+    value: Option<f32>,
+
+    /// The shader source and the counted loop the variant was instrumented from.
+    input: (String, Option<usize>),
+    variant: Option<Program>,
+    overlay: Option<Pass>,
+    reduction: Reduction,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            only: None,
+            automatic: true,
+            range: (0.0, 100.0),
+            opacity: 0.8,
+            loops: Vec::new(),
+            extent: None,
+            value: None,
+            input: (String::new(), None),
+            variant: None,
+            overlay: None,
+            reduction: Reduction::new(EXTENT),
+        }
+    }
+}
+
+impl Heatmap {
+    /// Recompile the instrumented variant if the shader or the counted loops changed,
+    /// returning whether it changed.
+    pub fn update(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        shader: Option<&Shader>,
+    ) -> Result<bool, Error> {
+        let source = match shader {
+            Some(shader) if self.enabled => shader.source(),
+            _ => "",
+        };
+
+        // Don't instrument nor recompile the same variant every frame, even if erroneous
+        if source == self.input.0 && self.only == self.input.1 {
+            return Ok(false);
+        }
+
+        if !source.is_empty() {
+            self.loops = instrument::loops(source);
+            if matches!(self.only, Some(line) if !self.loops.contains(&line)) {
+                self.only = None;
+            }
+        }
+
+        self.input = (source.to_owned(), self.only);
+        self.variant = None;
+        self.extent = None;
+        self.value = None;
+        self.reduction.reset();
+
+        if !source.is_empty() {
+            let variant = instrument::iterations(source, self.only);
+
+            self.variant = Some(
+                unsafe { Shader::compile(gl, &variant) }
+                    .map_err(|err| Error::Compile(format!("In heatmap variant, {err}")))?,
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// Render the iteration counts, reduce their extent and the count at `pixel` to be read back
+    /// a few frames later, and return the overlay of their false-color over `image` if enabled.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
+    pub unsafe fn render(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        uniforms: &Uniforms,
+        size: egui::Vec2,
+        image: glow::Texture,
+        pixel: Option<egui::Pos2>,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        let Some(program) = &self.variant else {
+            return Ok(None);
+        };
+
+        let counts = target::render_to_texture(gl, size.into(), Format::Rgba32F, || {
+            Shader::draw(gl, program, uniforms);
+        })?;
+
+        // Outside of the frame when not hovered
+        let pixel = pixel.map_or([-1, -1], |pixel| {
+            [pixel.x.floor() as i32, pixel.y.floor() as i32]
+        });
+        self.reduction
+            .reduce(gl, size, &[("_glou_input", *counts)], |program| {
+                gl.uniform_2_i32(
+                    gl.get_uniform_location(program, "_glou_pixel").as_ref(),
+                    pixel[0],
+                    pixel[1],
+                );
+            })?;
+        if let Some([hovered, max, min, value]) = self.reduction.collect(gl) {
+            self.extent = Some((min, max));
+            self.value = (hovered > 0.0).then_some(value);
+        }
+
+        let range = self.range();
+        let overlay = match &self.overlay {
+            Some(overlay) => overlay,
+            None => self
+                .overlay
+                .insert(Pass::new(gl, &format!("{OVERLAY}{TURBO}"))?),
+        };
+
+        overlay
+            .render_to_texture(
+                gl,
+                size,
+                Format::Rgba8,
+                &[("_glou_input", *counts), ("_glou_image", image)],
+                |program| {
+                    gl.uniform_2_f32(
+                        gl.get_uniform_location(program, "_glou_range").as_ref(),
+                        range.0,
+                        range.1,
+                    );
+                    gl.uniform_1_f32(
+                        gl.get_uniform_location(program, "_glou_opacity").as_ref(),
+                        self.opacity,
+                    );
+                },
+            )
+            .map(Some)
+    }
+
+    /// The counts mapped to the ends of the colormap.
+    pub fn range(&self) -> (f32, f32) {
+        match (self.automatic, self.extent) {
+            (true, Some(extent)) => extent,
+            _ => self.range,
+        }
+    }
+
+    pub fn loops(&self) -> &[usize] {
+        &self.loops
+    }
+
+    pub fn extent(&self) -> Option<(f32, f32)> {
+        self.extent
+    }
+
+    pub fn value(&self) -> Option<f32> {
+        self.value
+    }
+
+    /// The color of the colormap at `x`, between `0` and `1`, as computed by the overlay.
+    pub fn color(x: f32) -> egui::Color32 {
+        const R: [f64; 6] = [
+            0.13572138,
+            4.6153926,
+            -42.66032258,
+            132.13108234,
+            -152.94239396,
+            59.28637943,
+        ];
+        const G: [f64; 6] = [
+            0.09140261,
+            2.19418839,
+            4.84296658,
+            -14.18503333,
+            4.27729857,
+            2.82956604,
+        ];
+        const B: [f64; 6] = [
+            0.1066733,
+            12.64194608,
+            -60.58204836,
+            110.36276771,
+            -89.90310912,
+            27.34824973,
+        ];
+
+        let x = x.clamp(0.0, 1.0) as f64;
+        let channel = |coefficients: [f64; 6]| {
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(0.0, |value, c| value * x + c);
+
+            (value.clamp(0.0, 1.0) * 255.0) as u8
+        };
+
+        egui::Color32::from_rgb(channel(R), channel(G), channel(B))
+    }
+}
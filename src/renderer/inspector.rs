@@ -0,0 +1,177 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{readback::Readback, Format, Pool};
+use crate::Error;
+
+/// Reads the values around the hovered pixel back asynchronously, while enabled.
+#[derive(Debug, Default)]
+pub struct Inspector {
+    /// Read the hovered pixel, while the inspector is shown.
+    pub enabled: bool,
+
+    /// The reads in flight, with the hovered pixel, the size of the texture and the read region.
+    readback: Readback<(egui::Pos2, egui::Vec2, egui::Rect)>,
+    buffer: Vec<u8>,
+    inspection: Option<Inspection>,
+}
+
+impl Inspector {
+    /// The latest values read back around the hovered pixel, a frame or two late.
+    pub fn inspection(&self) -> Option<&Inspection> {
+        self.inspection.as_ref()
+    }
+
+    /// Collect the completed reads, and start reading the pixels around `pixel`, in OpenGL coordinates,
+    /// from `texture` of size `size` if enabled.
+    pub(super) unsafe fn inspect(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        size: egui::Vec2,
+        pixel: Option<egui::Pos2>,
+    ) -> Result<(), Error> {
+        let read = self.readback.collect(gl, &mut self.buffer);
+
+        let Some(pixel) = pixel.filter(|_| self.enabled) else {
+            self.inspection = None;
+
+            return Ok(());
+        };
+
+        if let Some((pixel, size, region)) = read {
+            let pixels = self
+                .buffer
+                .chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect::<Vec<_>>();
+
+            self.inspection = Some(Inspection::new(pixel, size, region, &pixels));
+        }
+
+        let region = Inspection::region(pixel, size);
+        if region.is_positive() {
+            self.readback.read_region(
+                gl,
+                texture,
+                region,
+                Format::Rgba32F,
+                (pixel, size, region),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The values read back from the rendered texture around the hovered pixel.
+#[derive(Debug, Clone, Copy)]
+pub struct Inspection {
+    /// The `gl_FragCoord.xy` of the hovered pixel.
+    pub coord: egui::Pos2,
+    /// The normalized coordinates of the hovered pixel.
+    pub uv: egui::Vec2,
+    /// The unclamped color of the hovered pixel.
+    pub rgba: [f32; 4],
+    /// The colors surrounding the hovered pixel, row by row from the top.
+    pub neighbourhood: [[Option<[f32; 4]>; Inspection::SIZE]; Inspection::SIZE],
+}
+
+impl Inspection {
+    /// The width and height of the neighbourhood read around the hovered pixel.
+    pub const SIZE: usize = 5;
+
+    /// Read the pixels around `pixel`, in OpenGL coordinates, from the render target `texture` of size `size`,
+    /// waiting for the render to complete.
+    pub(super) unsafe fn read(
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        size: egui::Vec2,
+        pixel: egui::Pos2,
+    ) -> Result<Self, Error> {
+        let framebuffer = Pool::framebuffer(texture)
+            .ok_or_else(|| Error::Gl("Unable to inspect a texture out of the pool".into()))?;
+        let region = Self::region(pixel, size);
+
+        let mut pixels = vec![0f32; Self::SIZE * Self::SIZE * 4];
+        if region.is_positive() {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(framebuffer));
+            gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            gl.read_pixels(
+                region.min.x as i32,
+                region.min.y as i32,
+                region.width() as i32,
+                region.height() as i32,
+                glow::RGBA,
+                glow::FLOAT,
+                glow::PixelPackData::Slice(bytemuck::cast_slice_mut(&mut pixels)),
+            );
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        }
+
+        Ok(Self::new(pixel, size, region, &pixels))
+    }
+
+    /// The region of the neighbourhood of `pixel`, clamped to the bounds of a texture of size `size`.
+    fn region(pixel: egui::Pos2, size: egui::Vec2) -> egui::Rect {
+        let radius = (Self::SIZE / 2) as f32;
+
+        egui::Rect::from_min_max(
+            (pixel - egui::Vec2::splat(radius)).max(egui::Pos2::ZERO),
+            (pixel + egui::Vec2::splat(radius + 1.0)).min(size.to_pos2()),
+        )
+    }
+
+    /// The values around `pixel`, in OpenGL coordinates, in a texture of size `size`,
+    /// from the RGBA `pixels` read in `region`.
+    fn new(pixel: egui::Pos2, size: egui::Vec2, region: egui::Rect, pixels: &[f32]) -> Self {
+        let (x, y) = (pixel.x as i32, pixel.y as i32);
+        let radius = (Self::SIZE / 2) as i32;
+
+        let (left, bottom) = (region.min.x as i32, region.min.y as i32);
+        let (right, top) = (region.max.x as i32 - 1, region.max.y as i32 - 1);
+
+        let stride = (right - left + 1) as usize;
+        let texel = |x: i32, y: i32| {
+            let inside = (left..=right).contains(&x) && (bottom..=top).contains(&y);
+            let index = ((y - bottom) as usize * stride + (x - left) as usize) * 4;
+
+            inside
+                .then(|| pixels.get(index..index + 4))
+                .flatten()
+                .map(|texel| [texel[0], texel[1], texel[2], texel[3]])
+        };
+
+        let mut neighbourhood = [[None; Self::SIZE]; Self::SIZE];
+        for (row, line) in neighbourhood.iter_mut().enumerate() {
+            for (column, value) in line.iter_mut().enumerate() {
+                *value = texel(x - radius + column as i32, y + radius - row as i32);
+            }
+        }
+
+        let coord = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+
+        Self {
+            coord,
+            uv: egui::vec2(coord.x / size.x, coord.y / size.y),
+            rgba: texel(x, y).unwrap_or_default(),
+            neighbourhood,
+        }
+    }
+
+    /// The color as 8-bit values, clamped to the displayable range.
+    pub fn rgba8(&self) -> [u8; 4] {
+        self.rgba
+            .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    pub fn hex(&self) -> String {
+        let [r, g, b, a] = self.rgba8();
+
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
@@ -0,0 +1,419 @@
+//! Rewriting of fragment shader sources into instrumented variants,
+//! where the user's `main` is renamed and wrapped to output debugging values.
+
+use crate::Error;
+
+/// Overloads converting any scalar or vector value into a `vec4`.
+const PRELUDE: &str = r#"
+vec4 _glou_debug = vec4(0.0);
+
+vec4 _glou_value(float v) { return vec4(v, 0.0, 0.0, 1.0); }
+vec4 _glou_value(vec2 v) { return vec4(v, 0.0, 1.0); }
+vec4 _glou_value(vec3 v) { return vec4(v, 1.0); }
+vec4 _glou_value(vec4 v) { return v; }
+vec4 _glou_value(int v) { return vec4(float(v), 0.0, 0.0, 1.0); }
+vec4 _glou_value(ivec2 v) { return vec4(vec2(v), 0.0, 1.0); }
+vec4 _glou_value(ivec3 v) { return vec4(vec3(v), 1.0); }
+vec4 _glou_value(ivec4 v) { return vec4(v); }
+vec4 _glou_value(bool v) { return vec4(v ? 1.0 : 0.0, 0.0, 0.0, 1.0); }
+"#;
+
+/// The output declared for shaders writing to `gl_FragColor`, unavailable in the core profile.
+const OUTPUT: &str = "_glou_output";
+
+/// Rewrite `source` so that it outputs the value of `expression`, as evaluated right after `line`,
+/// which must end a statement or a block of a function body.
+pub fn expression(source: &str, line: usize, expression: &str) -> Result<String, Error> {
+    if !in_function(&strip_comments(source), line) {
+        return Err(Error::Compile(format!(
+            "Unable to debug after line {line}, which doesn't end a statement of a function body"
+        )));
+    }
+
+    Ok(wrap(source, PRELUDE, "", |number, output| {
+        if number == line {
+            output.push_str(&format!("_glou_debug = _glou_value({expression});\n"));
+            output.push_str(&format!("#line {}\n", number + 1));
+        }
+    }))
+}
+
+/// Rewrite `source` so that it outputs in the red channel how many loop iterations were run,
+/// counting only the loop starting at line `only` if specified.
+pub fn iterations(source: &str, only: Option<usize>) -> String {
+    let source = count_loops(&strip_comments(source), 1, only, &mut Vec::new());
+
+    wrap(
+        &source,
+        "vec4 _glou_debug = vec4(0.0);\nint _glou_iterations = 0;\n",
+        "_glou_debug = vec4(float(_glou_iterations), 0.0, 0.0, 1.0);",
+        |_, _| (),
+    )
+}
+
+/// The lines at which the loops of `source` start.
+pub fn loops(source: &str) -> Vec<usize> {
+    let mut loops = Vec::new();
+    count_loops(&strip_comments(source), 1, None, &mut loops);
+
+    loops
+}
+
+/// Rename the user's `main`, inject the `prelude` and a new `main` running `epilogue` after the user's one
+/// and writing `_glou_debug` to the shader output, calling `inject` after each line of the source, with its 1-based number.
+fn wrap(
+    source: &str,
+    prelude: &str,
+    epilogue: &str,
+    mut inject: impl FnMut(usize, &mut String),
+) -> String {
+    let source = strip_comments(source);
+    let (source, output) = match output(&source) {
+        output if output == "gl_FragColor" => (rename(&source, "gl_FragColor", OUTPUT), None),
+        output => (source, Some(output)),
+    };
+    let source = rename(&source, "main", "_glou_main");
+
+    let mut lines = source.lines().enumerate().peekable();
+    let mut wrapped = String::with_capacity(source.len() + prelude.len());
+
+    // The `#version` directive must stay the first line of the source
+    if let Some((_, line)) = lines.next_if(|(_, line)| line.trim_start().starts_with("#version")) {
+        wrapped.push_str(line);
+        wrapped.push('\n');
+    }
+
+    if output.is_none() {
+        wrapped.push_str(&format!("out vec4 {OUTPUT};\n"));
+    }
+    wrapped.push_str(prelude);
+    wrapped.push_str(&format!(
+        "#line {}\n",
+        lines.peek().map(|(number, _)| number + 1).unwrap_or(1)
+    ));
+
+    for (number, line) in lines {
+        wrapped.push_str(line);
+        wrapped.push('\n');
+
+        inject(number + 1, &mut wrapped);
+    }
+
+    wrapped.push_str(&format!(
+        "\nvoid main() {{\n    _glou_main();\n    {epilogue}\n    {} = _glou_debug;\n}}\n",
+        output.as_deref().unwrap_or(OUTPUT)
+    ));
+
+    wrapped
+}
+
+/// Whether the 1-based `line` of `source`, without comments, ends a statement or a block of a function body,
+/// tracking the depth of the braces, a function body being opened right after a `)`.
+fn in_function(source: &str, line: usize) -> bool {
+    let (mut depth, mut function, mut previous) = (0usize, false, ' ');
+
+    for (number, text) in source.lines().enumerate() {
+        for c in text.chars() {
+            match c {
+                '{' => {
+                    if depth == 0 {
+                        function = previous == ')';
+                    }
+                    depth += 1;
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+
+            if !c.is_whitespace() {
+                previous = c;
+            }
+        }
+
+        if number + 1 == line {
+            return depth > 0 && function && text.trim_end().ends_with([';', '{', '}']);
+        }
+    }
+
+    false
+}
+
+/// Find the name of the `out vec4` declared by the shader, or fallback to `gl_FragColor`.
+pub(super) fn output(source: &str) -> String {
+    source
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = match line.strip_prefix("layout") {
+                Some(rest) => &rest[rest.find(')')? + 1..],
+                None => line,
+            };
+
+            let mut tokens = line
+                .split_whitespace()
+                .filter(|token| !matches!(*token, "lowp" | "mediump" | "highp"));
+
+            match (tokens.next(), tokens.next(), tokens.next()) {
+                (Some("out"), Some("vec4"), Some(name)) => {
+                    Some(name.trim_end_matches(';').to_string())
+                }
+                _ => None,
+            }
+        })
+        .next()
+        .unwrap_or_else(|| "gl_FragColor".into())
+}
+
+/// Blank out the comments of `source`, preserving the line numbers.
+pub(super) fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => while chars.next_if(|c| *c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push('\n');
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+pub(super) fn is_identifier(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Replace every occurence of the identifier `from` to `to` in `source`.
+pub(super) fn rename(source: &str, from: &str, to: &str) -> String {
+    let mut renamed = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find(|c: char| is_identifier(c)) {
+        let end = rest[start..]
+            .find(|c: char| !is_identifier(c))
+            .map(|end| start + end)
+            .unwrap_or(rest.len());
+
+        renamed.push_str(&rest[..start]);
+        renamed.push_str(if &rest[start..end] == from {
+            to
+        } else {
+            &rest[start..end]
+        });
+
+        rest = &rest[end..];
+    }
+    renamed.push_str(rest);
+
+    renamed
+}
+
+/// Insert an iteration counter increment at the beginning of each loop body of `source`, whose first line is `first`,
+/// or only of the loop at line `only`, collecting the lines of the loops in `loops`.
+fn count_loops(source: &str, first: usize, only: Option<usize>, loops: &mut Vec<usize>) -> String {
+    const INCREMENT: &str = " _glou_iterations++; ";
+
+    let line = |rest: &str| first + source[..source.len() - rest.len()].matches('\n').count();
+
+    let mut counted = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find(is_identifier) {
+        let end = rest[start..]
+            .find(|c: char| !is_identifier(c))
+            .map(|end| start + end)
+            .unwrap_or(rest.len());
+
+        let number = line(&rest[start..]);
+        counted.push_str(&rest[..end]);
+
+        let keyword = &rest[start..end];
+        rest = &rest[end..];
+
+        // Skip the `for (..)` or `while (..)` header to find the body
+        let header = match keyword {
+            "for" | "while" if rest.trim_start().starts_with('(') => balanced(rest),
+            "do" => Some(0),
+            _ => None,
+        };
+        let Some(header) = header else {
+            continue;
+        };
+
+        let body = header + rest[header..].len() - rest[header..].trim_start().len();
+        // The `while (..);` tail of a `do` loop has no body
+        if rest[body..].starts_with(';') {
+            continue;
+        }
+
+        loops.push(number);
+        let increment = if only.map_or(true, |only| only == number) {
+            INCREMENT
+        } else {
+            ""
+        };
+
+        counted.push_str(&rest[..body]);
+        rest = &rest[body..];
+
+        if let Some(block) = rest.strip_prefix('{') {
+            counted.push('{');
+            counted.push_str(increment);
+            rest = block;
+        } else if let Some(statement) = statement(rest) {
+            // Braceless single-statement bodies are wrapped in a block
+            counted.push('{');
+            counted.push_str(increment);
+            counted.push_str(&count_loops(&rest[..statement], line(rest), only, loops));
+            counted.push('}');
+            rest = &rest[statement..];
+        }
+    }
+    counted.push_str(rest);
+
+    counted
+}
+
+/// Find the end of the parenthesized expression at the start of `source`.
+fn balanced(source: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (index, c) in source.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => return Some(index + 1),
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Find the end of the single statement at the start of `source`, including any `else` branch.
+fn statement(source: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (index, c) in source.char_indices() {
+        let end = match c {
+            '(' | '{' => {
+                depth += 1;
+                continue;
+            }
+            '}' if depth == 1 => index + 1,
+            ';' if depth == 0 => index + 1,
+            ')' | '}' => {
+                depth -= 1;
+                continue;
+            }
+            _ => continue,
+        };
+
+        let next = source[end..].trim_start();
+        let chained = next.starts_with("else") && !next[4..].starts_with(is_identifier);
+        if !chained {
+            return Some(end);
+        }
+        depth = 0;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renamed() {
+        assert_eq!(
+            rename("void main() { mainImage(main); }", "main", "_glou_main"),
+            "void _glou_main() { mainImage(_glou_main); }"
+        );
+        assert_eq!(
+            rename("gl_FragColor=vec4(1.0);", "gl_FragColor", OUTPUT),
+            "_glou_output=vec4(1.0);"
+        );
+    }
+
+    #[test]
+    fn stripped() {
+        let source = "float a; // a comment\n/* a\nblock */ float b;\nfloat c = 1.0 / 2.0;";
+        let stripped = strip_comments(source);
+
+        assert_eq!(stripped, "float a; \n\n float b;\nfloat c = 1.0 / 2.0;");
+        assert_eq!(stripped.lines().count(), source.lines().count());
+    }
+
+    #[test]
+    fn outputs() {
+        assert_eq!(output("out vec4 color;\nvoid main() {}"), "color");
+        assert_eq!(
+            output("layout(location = 0) out highp vec4 fragColor;"),
+            "fragColor"
+        );
+        assert_eq!(output("out vec3 normal;"), "gl_FragColor");
+        assert_eq!(output("void main() {}"), "gl_FragColor");
+    }
+
+    #[test]
+    fn debugged() {
+        let source = "#version 330 core\nout vec4 color;\nfloat f(float x)\n{\n    float y = x *\n        2.0;\n    return y;\n}\nvoid main() {\n    if (true) {\n        color = vec4(f(1.0));\n    }\n}\n";
+
+        let variant = expression(source, 6, "y").expect("Line 6 ends a statement");
+        assert!(variant.contains("        2.0;\n_glou_debug = _glou_value(y);\n#line 7\n"));
+        for line in [4, 9, 11, 12] {
+            assert!(expression(source, line, "color").is_ok(), "Line {line}");
+        }
+
+        // Out of the function bodies, or in the middle of a statement
+        for line in [1, 2, 3, 5, 8, 13, 14] {
+            assert!(expression(source, line, "color").is_err(), "Line {line}");
+        }
+    }
+
+    #[test]
+    fn counted() {
+        let source = "void main() {\n    for (int i = 0; i < 4; i++) {\n        while (true) x++;\n    }\n    do { x--; } while (x > 0);\n}\n";
+
+        let mut lines = Vec::new();
+        let counted = count_loops(source, 1, None, &mut lines);
+        assert_eq!(lines, [2, 3, 5]);
+        assert_eq!(counted.matches("_glou_iterations++").count(), 3);
+        // The braceless body is wrapped in a block, and the `while` tail of the `do` is left alone
+        assert!(counted.contains("while (true) { _glou_iterations++; x++;}"));
+        assert!(counted.contains("} while (x > 0);"));
+        assert_eq!(counted.lines().count(), source.lines().count());
+
+        let counted = count_loops(source, 1, Some(3), &mut Vec::new());
+        assert_eq!(counted.matches("_glou_iterations++").count(), 1);
+        assert!(counted.contains("while (true) { _glou_iterations++; x++;}"));
+    }
+
+    #[test]
+    fn iterated() {
+        let source = "#version 330 core\nout vec4 color;\n// for (;;) {}\nvoid main() {\n    for (int i = 0; i < 4; i++) {}\n}\n";
+
+        assert_eq!(loops(source), [5]);
+
+        let variant = iterations(source, None);
+        assert!(variant.starts_with("#version 330 core\n"));
+        assert!(variant.contains("void _glou_main()"));
+        assert!(variant.contains("color = _glou_debug;"));
+        assert_eq!(variant.matches("_glou_iterations++").count(), 1);
+    }
+}
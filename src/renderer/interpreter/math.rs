@@ -0,0 +1,570 @@
+//! The operators and builtin functions of the interpreter.
+
+use std::cmp::Ordering;
+
+use naga::{BinaryOperator, MathFunction, RelationalFunction, ScalarKind, UnaryOperator};
+
+use super::value::{Scalar, Value};
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Apply `f` to the components of scalars and equally-sized vectors, broadcasting scalars.
+pub fn componentwise(args: &[&Value], f: impl Fn(&[Scalar]) -> Result<Scalar>) -> Result<Value> {
+    let args = args
+        .iter()
+        .map(|arg| arg.components())
+        .collect::<Result<Vec<_>>>()?;
+    let size = args.iter().map(Vec::len).max().unwrap_or(1);
+
+    let mut components = Vec::with_capacity(size);
+    let mut scalars = Vec::with_capacity(args.len());
+    for index in 0..size {
+        scalars.clear();
+
+        for arg in &args {
+            scalars.push(match arg.as_slice() {
+                [scalar] => *scalar,
+                arg => *arg.get(index).ok_or("Mismatched vector sizes")?,
+            });
+        }
+
+        components.push(f(&scalars)?);
+    }
+
+    Ok(Value::from_components(components))
+}
+
+/// Apply `f` to the floating-point components, broadcasting scalars.
+fn floats(args: &[&Value], f: impl Fn(&[f32]) -> f32) -> Result<Value> {
+    componentwise(args, |scalars| {
+        let floats = scalars
+            .iter()
+            .map(|scalar| scalar.float())
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Scalar::Float(f(&floats)))
+    })
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Multiply a matrix, column by column, by a column vector.
+fn transform(matrix: &[Vec<f32>], vector: &[f32]) -> Vec<f32> {
+    let rows = matrix.first().map(Vec::len).unwrap_or_default();
+
+    (0..rows)
+        .map(|row| {
+            matrix
+                .iter()
+                .zip(vector)
+                .map(|(column, x)| column[row] * x)
+                .sum()
+        })
+        .collect()
+}
+
+fn transpose(matrix: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let rows = matrix.first().map(Vec::len).unwrap_or_default();
+
+    (0..rows)
+        .map(|row| matrix.iter().map(|column| column[row]).collect())
+        .collect()
+}
+
+/// Invert a square matrix by Gauss-Jordan elimination, also returning its determinant.
+fn invert(matrix: &[Vec<f32>]) -> (Vec<Vec<f32>>, f32) {
+    let size = matrix.len();
+    let mut matrix = matrix
+        .iter()
+        .map(|line| line.iter().map(|x| *x as f64).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut inverse = (0..size)
+        .map(|i| {
+            (0..size)
+                .map(|j| if i == j { 1.0 } else { 0.0 })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut determinant = 1.0;
+
+    for i in 0..size {
+        let pivot = (i..size)
+            .max_by(|a, b| matrix[*a][i].abs().total_cmp(&matrix[*b][i].abs()))
+            .unwrap_or(i);
+        if matrix[pivot][i] == 0.0 {
+            return (vec![vec![f32::NAN; size]; size], 0.0);
+        }
+        if pivot != i {
+            matrix.swap(pivot, i);
+            inverse.swap(pivot, i);
+            determinant = -determinant;
+        }
+
+        let factor = matrix[i][i];
+        determinant *= factor;
+        for j in 0..size {
+            matrix[i][j] /= factor;
+            inverse[i][j] /= factor;
+        }
+
+        for k in (0..size).filter(|k| *k != i) {
+            let factor = matrix[k][i];
+            for j in 0..size {
+                matrix[k][j] -= factor * matrix[i][j];
+                inverse[k][j] -= factor * inverse[i][j];
+            }
+        }
+    }
+
+    (
+        inverse
+            .into_iter()
+            .map(|line| line.into_iter().map(|x| x as f32).collect())
+            .collect(),
+        determinant as f32,
+    )
+}
+
+fn order(a: Scalar, b: Scalar) -> Option<Ordering> {
+    match (a, b) {
+        (Scalar::Float(a), Scalar::Float(b)) => a.partial_cmp(&b),
+        (Scalar::Sint(a), Scalar::Sint(b)) => Some(a.cmp(&b)),
+        (Scalar::Uint(a), Scalar::Uint(b)) => Some(a.cmp(&b)),
+        _ => None,
+    }
+}
+
+fn arithmetic(
+    a: Scalar,
+    b: Scalar,
+    float: fn(f32, f32) -> f32,
+    sint: fn(i32, i32) -> Option<i32>,
+    uint: fn(u32, u32) -> Option<u32>,
+) -> Option<Scalar> {
+    match (a, b) {
+        (Scalar::Float(a), Scalar::Float(b)) => Some(Scalar::Float(float(a, b))),
+        // Integer division by zero is undefined, and evaluates to zero
+        (Scalar::Sint(a), Scalar::Sint(b)) => Some(Scalar::Sint(sint(a, b).unwrap_or_default())),
+        (Scalar::Uint(a), Scalar::Uint(b)) => Some(Scalar::Uint(uint(a, b).unwrap_or_default())),
+        _ => None,
+    }
+}
+
+fn scalar_binary(op: BinaryOperator, a: Scalar, b: Scalar) -> Result<Scalar> {
+    use BinaryOperator as Op;
+
+    let result = match op {
+        Op::Add => arithmetic(
+            a,
+            b,
+            |a, b| a + b,
+            |a, b| Some(a.wrapping_add(b)),
+            |a, b| Some(a.wrapping_add(b)),
+        ),
+        Op::Subtract => arithmetic(
+            a,
+            b,
+            |a, b| a - b,
+            |a, b| Some(a.wrapping_sub(b)),
+            |a, b| Some(a.wrapping_sub(b)),
+        ),
+        Op::Multiply => arithmetic(
+            a,
+            b,
+            |a, b| a * b,
+            |a, b| Some(a.wrapping_mul(b)),
+            |a, b| Some(a.wrapping_mul(b)),
+        ),
+        Op::Divide => arithmetic(a, b, |a, b| a / b, i32::checked_div, u32::checked_div),
+        Op::Modulo => arithmetic(a, b, |a, b| a % b, i32::checked_rem, u32::checked_rem),
+        Op::Equal => Some(Scalar::Bool(a == b)),
+        Op::NotEqual => Some(Scalar::Bool(a != b)),
+        Op::Less => Some(Scalar::Bool(order(a, b) == Some(Ordering::Less))),
+        Op::LessEqual => Some(Scalar::Bool(matches!(
+            order(a, b),
+            Some(Ordering::Less | Ordering::Equal)
+        ))),
+        Op::Greater => Some(Scalar::Bool(order(a, b) == Some(Ordering::Greater))),
+        Op::GreaterEqual => Some(Scalar::Bool(matches!(
+            order(a, b),
+            Some(Ordering::Greater | Ordering::Equal)
+        ))),
+        Op::And | Op::LogicalAnd => match (a, b) {
+            (Scalar::Bool(a), Scalar::Bool(b)) => Some(Scalar::Bool(a && b)),
+            (Scalar::Sint(a), Scalar::Sint(b)) => Some(Scalar::Sint(a & b)),
+            (Scalar::Uint(a), Scalar::Uint(b)) => Some(Scalar::Uint(a & b)),
+            _ => None,
+        },
+        Op::InclusiveOr | Op::LogicalOr => match (a, b) {
+            (Scalar::Bool(a), Scalar::Bool(b)) => Some(Scalar::Bool(a || b)),
+            (Scalar::Sint(a), Scalar::Sint(b)) => Some(Scalar::Sint(a | b)),
+            (Scalar::Uint(a), Scalar::Uint(b)) => Some(Scalar::Uint(a | b)),
+            _ => None,
+        },
+        Op::ExclusiveOr => match (a, b) {
+            (Scalar::Bool(a), Scalar::Bool(b)) => Some(Scalar::Bool(a ^ b)),
+            (Scalar::Sint(a), Scalar::Sint(b)) => Some(Scalar::Sint(a ^ b)),
+            (Scalar::Uint(a), Scalar::Uint(b)) => Some(Scalar::Uint(a ^ b)),
+            _ => None,
+        },
+        Op::ShiftLeft | Op::ShiftRight => {
+            let shift = b.index()? as u32;
+
+            match (op, a) {
+                (Op::ShiftLeft, Scalar::Sint(a)) => Some(Scalar::Sint(a.wrapping_shl(shift))),
+                (Op::ShiftLeft, Scalar::Uint(a)) => Some(Scalar::Uint(a.wrapping_shl(shift))),
+                (_, Scalar::Sint(a)) => Some(Scalar::Sint(a.wrapping_shr(shift))),
+                (_, Scalar::Uint(a)) => Some(Scalar::Uint(a.wrapping_shr(shift))),
+                _ => None,
+            }
+        }
+    };
+
+    result.ok_or_else(|| format!("Unsupported operation `{a} {op:?} {b}`"))
+}
+
+pub fn binary(op: BinaryOperator, left: &Value, right: &Value) -> Result<Value> {
+    use BinaryOperator as Op;
+
+    match (op, left, right) {
+        (Op::Multiply, Value::Matrix(a), Value::Matrix(b)) => Ok(Value::Matrix(
+            b.iter().map(|column| transform(a, column)).collect(),
+        )),
+        (Op::Multiply, Value::Matrix(a), Value::Vector(_)) => {
+            Ok(Value::from_floats(transform(a, &right.floats()?)))
+        }
+        (Op::Multiply, Value::Vector(_), Value::Matrix(b)) => {
+            let vector = left.floats()?;
+
+            Ok(Value::from_floats(
+                b.iter().map(|column| dot(&vector, column)),
+            ))
+        }
+        (Op::Multiply, Value::Matrix(a), Value::Scalar(x))
+        | (Op::Multiply, Value::Scalar(x), Value::Matrix(a)) => {
+            let x = x.float()?;
+
+            Ok(Value::Matrix(
+                a.iter()
+                    .map(|column| column.iter().map(|y| x * y).collect())
+                    .collect(),
+            ))
+        }
+        (_, Value::Matrix(a), Value::Matrix(b)) => Ok(Value::Matrix(
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| {
+                    binary(
+                        op,
+                        &Value::from_floats(a.clone()),
+                        &Value::from_floats(b.clone()),
+                    )?
+                    .floats()
+                })
+                .collect::<Result<_>>()?,
+        )),
+        _ => componentwise(&[left, right], |scalars| {
+            scalar_binary(op, scalars[0], scalars[1])
+        }),
+    }
+}
+
+pub fn unary(op: UnaryOperator, value: &Value) -> Result<Value> {
+    if let (UnaryOperator::Negate, Value::Matrix(columns)) = (op, value) {
+        return Ok(Value::Matrix(
+            columns
+                .iter()
+                .map(|column| column.iter().map(|x| -x).collect())
+                .collect(),
+        ));
+    }
+
+    componentwise(&[value], |scalars| match (op, scalars[0]) {
+        (UnaryOperator::Negate, Scalar::Float(x)) => Ok(Scalar::Float(-x)),
+        (UnaryOperator::Negate, Scalar::Sint(x)) => Ok(Scalar::Sint(x.wrapping_neg())),
+        (UnaryOperator::LogicalNot | UnaryOperator::BitwiseNot, Scalar::Bool(x)) => {
+            Ok(Scalar::Bool(!x))
+        }
+        (UnaryOperator::BitwiseNot, Scalar::Sint(x)) => Ok(Scalar::Sint(!x)),
+        (UnaryOperator::BitwiseNot, Scalar::Uint(x)) => Ok(Scalar::Uint(!x)),
+        (op, x) => Err(format!("Unsupported operation `{op:?} {x}`")),
+    })
+}
+
+pub fn relational(fun: RelationalFunction, value: &Value) -> Result<Value> {
+    match fun {
+        RelationalFunction::All | RelationalFunction::Any => {
+            let components = value
+                .components()?
+                .into_iter()
+                .map(Scalar::bool)
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(Value::Scalar(Scalar::Bool(match fun {
+                RelationalFunction::All => components.iter().all(|x| *x),
+                _ => components.iter().any(|x| *x),
+            })))
+        }
+        RelationalFunction::IsNan => componentwise(&[value], |scalars| {
+            Ok(Scalar::Bool(scalars[0].float()?.is_nan()))
+        }),
+        RelationalFunction::IsInf => componentwise(&[value], |scalars| {
+            Ok(Scalar::Bool(scalars[0].float()?.is_infinite()))
+        }),
+    }
+}
+
+/// Convert the components of `value` to `kind`, or reinterpret their bits if not `numeric`.
+pub fn convert(value: &Value, kind: ScalarKind, numeric: bool) -> Result<Value> {
+    if let Value::Matrix(_) = value {
+        return Ok(value.clone());
+    }
+
+    componentwise(&[value], |scalars| {
+        let x = scalars[0];
+
+        Ok(match (kind, x, numeric) {
+            (ScalarKind::Float, Scalar::Float(x), _) => Scalar::Float(x),
+            (ScalarKind::Float, Scalar::Sint(x), true) => Scalar::Float(x as f32),
+            (ScalarKind::Float, Scalar::Uint(x), true) => Scalar::Float(x as f32),
+            (ScalarKind::Float, Scalar::Bool(x), _) => Scalar::Float(x as u8 as f32),
+            (ScalarKind::Float, Scalar::Sint(x), false) => Scalar::Float(f32::from_bits(x as u32)),
+            (ScalarKind::Float, Scalar::Uint(x), false) => Scalar::Float(f32::from_bits(x)),
+            (ScalarKind::Sint, Scalar::Float(x), true) => Scalar::Sint(x as i32),
+            (ScalarKind::Sint, Scalar::Float(x), false) => Scalar::Sint(x.to_bits() as i32),
+            (ScalarKind::Sint, Scalar::Sint(x), _) => Scalar::Sint(x),
+            (ScalarKind::Sint, Scalar::Uint(x), _) => Scalar::Sint(x as i32),
+            (ScalarKind::Sint, Scalar::Bool(x), _) => Scalar::Sint(x as i32),
+            (ScalarKind::Uint, Scalar::Float(x), true) => Scalar::Uint(x as u32),
+            (ScalarKind::Uint, Scalar::Float(x), false) => Scalar::Uint(x.to_bits()),
+            (ScalarKind::Uint, Scalar::Sint(x), _) => Scalar::Uint(x as u32),
+            (ScalarKind::Uint, Scalar::Uint(x), _) => Scalar::Uint(x),
+            (ScalarKind::Uint, Scalar::Bool(x), _) => Scalar::Uint(x as u32),
+            (ScalarKind::Bool, Scalar::Float(x), _) => Scalar::Bool(x != 0.0),
+            (ScalarKind::Bool, Scalar::Sint(x), _) => Scalar::Bool(x != 0),
+            (ScalarKind::Bool, Scalar::Uint(x), _) => Scalar::Bool(x != 0),
+            (ScalarKind::Bool, Scalar::Bool(x), _) => Scalar::Bool(x),
+        })
+    })
+}
+
+/// Round to the nearest integer, halfway cases to the even one.
+fn round(x: f32) -> f32 {
+    let rounded = x.round();
+
+    if (x - x.trunc()).abs() == 0.5 {
+        2.0 * (x / 2.0).round()
+    } else {
+        rounded
+    }
+}
+
+pub fn math(fun: MathFunction, args: &[&Value]) -> Result<Value> {
+    use MathFunction as Mf;
+
+    let arg = |index: usize| {
+        args.get(index)
+            .copied()
+            .ok_or_else(|| format!("Missing argument to `{fun:?}`"))
+    };
+    let float = |f: fn(f32) -> f32| floats(&args[..1], |x| f(x[0]));
+
+    match fun {
+        Mf::Abs => componentwise(&[arg(0)?], |x| match x[0] {
+            Scalar::Float(x) => Ok(Scalar::Float(x.abs())),
+            Scalar::Sint(x) => Ok(Scalar::Sint(x.wrapping_abs())),
+            x => Ok(x),
+        }),
+        Mf::Min | Mf::Max | Mf::Clamp => {
+            let pick = |a: Scalar, b: Scalar, ordering| -> Result<Scalar> {
+                Ok(match (a, b) {
+                    (Scalar::Float(a), Scalar::Float(b)) => Scalar::Float(match ordering {
+                        Ordering::Less => a.min(b),
+                        _ => a.max(b),
+                    }),
+                    _ if order(a, b) == Some(ordering) => a,
+                    _ => b,
+                })
+            };
+
+            match fun {
+                Mf::Min => componentwise(&[arg(0)?, arg(1)?], |x| pick(x[0], x[1], Ordering::Less)),
+                Mf::Max => {
+                    componentwise(&[arg(0)?, arg(1)?], |x| pick(x[0], x[1], Ordering::Greater))
+                }
+                _ => componentwise(&[arg(0)?, arg(1)?, arg(2)?], |x| {
+                    pick(pick(x[0], x[1], Ordering::Greater)?, x[2], Ordering::Less)
+                }),
+            }
+        }
+        Mf::Saturate => float(|x| x.clamp(0.0, 1.0)),
+        Mf::Cos => float(f32::cos),
+        Mf::Cosh => float(f32::cosh),
+        Mf::Sin => float(f32::sin),
+        Mf::Sinh => float(f32::sinh),
+        Mf::Tan => float(f32::tan),
+        Mf::Tanh => float(f32::tanh),
+        Mf::Acos => float(f32::acos),
+        Mf::Asin => float(f32::asin),
+        Mf::Atan => float(f32::atan),
+        Mf::Atan2 => floats(&[arg(0)?, arg(1)?], |x| x[0].atan2(x[1])),
+        Mf::Asinh => float(f32::asinh),
+        Mf::Acosh => float(f32::acosh),
+        Mf::Atanh => float(f32::atanh),
+        Mf::Radians => float(f32::to_radians),
+        Mf::Degrees => float(f32::to_degrees),
+        Mf::Ceil => float(f32::ceil),
+        Mf::Floor => float(f32::floor),
+        Mf::Round => float(round),
+        Mf::Fract => float(|x| x - x.floor()),
+        Mf::Trunc => float(f32::trunc),
+        Mf::Ldexp => componentwise(&[arg(0)?, arg(1)?], |x| {
+            Ok(Scalar::Float(
+                x[0].float()? * 2f32.powi(x[1].index()? as i32),
+            ))
+        }),
+        Mf::Exp => float(f32::exp),
+        Mf::Exp2 => float(f32::exp2),
+        Mf::Log => float(f32::ln),
+        Mf::Log2 => float(f32::log2),
+        Mf::Pow => floats(&[arg(0)?, arg(1)?], |x| x[0].powf(x[1])),
+        Mf::Dot => Ok(Value::Scalar(Scalar::Float(dot(
+            &arg(0)?.floats()?,
+            &arg(1)?.floats()?,
+        )))),
+        Mf::Outer => {
+            let (a, b) = (arg(0)?.floats()?, arg(1)?.floats()?);
+
+            Ok(Value::Matrix(
+                b.iter()
+                    .map(|y| a.iter().map(|x| x * y).collect())
+                    .collect(),
+            ))
+        }
+        Mf::Cross => {
+            let (a, b) = (arg(0)?.floats()?, arg(1)?.floats()?);
+
+            match (a.as_slice(), b.as_slice()) {
+                ([ax, ay, az], [bx, by, bz]) => Ok(Value::from_floats([
+                    ay * bz - az * by,
+                    az * bx - ax * bz,
+                    ax * by - ay * bx,
+                ])),
+                _ => Err("Cross product of non-3-component vectors".into()),
+            }
+        }
+        Mf::Distance => {
+            let difference = binary(BinaryOperator::Subtract, arg(0)?, arg(1)?)?.floats()?;
+
+            Ok(Value::Scalar(Scalar::Float(
+                dot(&difference, &difference).sqrt(),
+            )))
+        }
+        Mf::Length => {
+            let x = arg(0)?.floats()?;
+
+            Ok(Value::Scalar(Scalar::Float(dot(&x, &x).sqrt())))
+        }
+        Mf::Normalize => {
+            let x = arg(0)?.floats()?;
+            let length = dot(&x, &x).sqrt();
+
+            Ok(Value::from_floats(x.iter().map(|x| x / length)))
+        }
+        Mf::FaceForward => {
+            let (n, i, reference) = (arg(0)?.floats()?, arg(1)?.floats()?, arg(2)?.floats()?);
+            let sign = if dot(&reference, &i) < 0.0 { 1.0 } else { -1.0 };
+
+            Ok(Value::from_floats(n.iter().map(|x| sign * x)))
+        }
+        Mf::Reflect => {
+            let (i, n) = (arg(0)?.floats()?, arg(1)?.floats()?);
+            let d = dot(&n, &i);
+
+            Ok(Value::from_floats(
+                i.iter().zip(&n).map(|(i, n)| i - 2.0 * d * n),
+            ))
+        }
+        Mf::Refract => {
+            let (i, n, eta) = (
+                arg(0)?.floats()?,
+                arg(1)?.floats()?,
+                arg(2)?.scalar()?.float()?,
+            );
+            let d = dot(&n, &i);
+            let k = 1.0 - eta * eta * (1.0 - d * d);
+
+            Ok(Value::from_floats(i.iter().zip(&n).map(|(i, n)| {
+                if k < 0.0 {
+                    0.0
+                } else {
+                    eta * i - (eta * d + k.sqrt()) * n
+                }
+            })))
+        }
+        Mf::Sign => componentwise(&[arg(0)?], |x| match x[0] {
+            Scalar::Float(x) if x > 0.0 => Ok(Scalar::Float(1.0)),
+            Scalar::Float(x) if x < 0.0 => Ok(Scalar::Float(-1.0)),
+            Scalar::Float(x) => Ok(Scalar::Float(x)),
+            Scalar::Sint(x) => Ok(Scalar::Sint(x.signum())),
+            x => Err(format!("Unsupported operation `sign({x})`")),
+        }),
+        Mf::Fma => floats(&[arg(0)?, arg(1)?, arg(2)?], |x| x[0].mul_add(x[1], x[2])),
+        Mf::Mix => floats(&[arg(0)?, arg(1)?, arg(2)?], |x| {
+            x[0] * (1.0 - x[2]) + x[1] * x[2]
+        }),
+        Mf::Step => floats(&[arg(0)?, arg(1)?], |x| if x[1] < x[0] { 0.0 } else { 1.0 }),
+        Mf::SmoothStep => floats(&[arg(0)?, arg(1)?, arg(2)?], |x| {
+            let t = ((x[2] - x[0]) / (x[1] - x[0])).clamp(0.0, 1.0);
+
+            t * t * (3.0 - 2.0 * t)
+        }),
+        Mf::Sqrt => float(f32::sqrt),
+        Mf::InverseSqrt => float(|x| 1.0 / x.sqrt()),
+        Mf::Transpose | Mf::Inverse | Mf::Determinant => {
+            let Value::Matrix(matrix) = arg(0)? else {
+                return Err(format!("Expected a matrix as argument to `{fun:?}`"));
+            };
+
+            Ok(match fun {
+                Mf::Transpose => Value::Matrix(transpose(matrix)),
+                Mf::Inverse => Value::Matrix(invert(matrix).0),
+                _ => Value::Scalar(Scalar::Float(invert(matrix).1)),
+            })
+        }
+        Mf::CountOneBits
+        | Mf::ReverseBits
+        | Mf::FindLsb
+        | Mf::FindMsb
+        | Mf::CountLeadingZeros
+        | Mf::CountTrailingZeros => componentwise(&[arg(0)?], |x| {
+            let (bits, signed) = match x[0] {
+                Scalar::Sint(x) => (x as u32, true),
+                Scalar::Uint(x) => (x, false),
+                x => return Err(format!("Unsupported operation `{fun:?}({x})`")),
+            };
+            let found = |position: u32| if position >= 32 { -1 } else { position as i32 };
+
+            let result = match fun {
+                Mf::CountOneBits => bits.count_ones(),
+                Mf::ReverseBits => bits.reverse_bits(),
+                Mf::FindLsb => found(bits.trailing_zeros()) as u32,
+                Mf::FindMsb if signed && (bits as i32) < 0 => {
+                    found(31u32.wrapping_sub((!bits).leading_zeros())) as u32
+                }
+                Mf::FindMsb => found(31u32.wrapping_sub(bits.leading_zeros())) as u32,
+                Mf::CountLeadingZeros => bits.leading_zeros(),
+                _ => bits.trailing_zeros(),
+            };
+
+            Ok(if signed {
+                Scalar::Sint(result as i32)
+            } else {
+                Scalar::Uint(result)
+            })
+        }),
+        fun => Err(format!("Unsupported builtin function `{fun:?}`")),
+    }
+}
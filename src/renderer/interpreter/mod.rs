@@ -0,0 +1,1164 @@
+//! A CPU interpreter of fragment shaders, executing naga's intermediate representation
+//! of the shader for a single pixel while recording each executed statement.
+
+use std::collections::BTreeMap;
+
+use naga::{
+    front::glsl, AddressSpace, ArraySize, Binding, Block, BuiltIn, Expression, Function,
+    GlobalVariable, Handle, ImageQuery, Literal, ScalarKind, Span, Statement, SwitchValue, Type,
+    TypeInner,
+};
+
+mod math;
+mod source;
+
+mod value;
+use value::{Pointer, Root};
+pub use value::{Scalar, Value};
+
+use crate::Error;
+
+/// The maximum number of recorded steps, execution continuing unrecorded past it.
+const MAX_STEPS: usize = 20_000;
+/// The maximum number of executed statements, to bail out of infinite loops.
+const MAX_STATEMENTS: usize = 1_000_000;
+/// The maximum depth of the call stack.
+const MAX_DEPTH: usize = 64;
+
+/// A fragment shader parsed for interpretation.
+#[derive(Debug)]
+pub struct Program {
+    module: naga::Module,
+    /// The offset of each line of the rewritten source.
+    starts: Vec<usize>,
+    /// The original line of each line of the rewritten source.
+    lines: Vec<usize>,
+}
+
+/// The inputs of the interpreted invocation.
+#[derive(Debug, Default)]
+pub struct Inputs {
+    /// The `gl_FragCoord.xy` of the pixel.
+    pub coord: [f32; 2],
+    pub uniforms: BTreeMap<String, Vec<f32>>,
+    pub textures: BTreeMap<String, Texture>,
+}
+
+/// The texels of a `sampler2D` uniform, row by row from the bottom.
+#[derive(Debug, Default)]
+pub struct Texture {
+    pub width: usize,
+    pub height: usize,
+    pub texels: Vec<[f32; 4]>,
+}
+
+impl Texture {
+    /// The texel at `(x, y)`, or zero outside of the texture like `texelFetch`.
+    pub fn fetch(&self, x: i64, y: i64) -> [f32; 4] {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            self.texels[y as usize * self.width + x as usize]
+        } else {
+            [0.0; 4]
+        }
+    }
+
+    /// The bilinearly filtered color at the normalized coordinates `(u, v)`, clamped to the edges.
+    pub fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0, 0.0, 0.0, 1.0];
+        }
+
+        let (x, y) = (u * self.width as f32 - 0.5, v * self.height as f32 - 0.5);
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        let texel = |x: f32, y: f32| {
+            self.fetch(
+                (x as i64).clamp(0, self.width as i64 - 1),
+                (y as i64).clamp(0, self.height as i64 - 1),
+            )
+        };
+
+        let (x, y) = (x.floor(), y.floor());
+        let (a, b, c, d) = (
+            texel(x, y),
+            texel(x + 1.0, y),
+            texel(x, y + 1.0),
+            texel(x + 1.0, y + 1.0),
+        );
+
+        [0, 1, 2, 3].map(|i| {
+            let bottom = a[i] * (1.0 - fx) + b[i] * fx;
+            let top = c[i] * (1.0 - fx) + d[i] * fx;
+
+            bottom * (1.0 - fy) + top * fy
+        })
+    }
+}
+
+/// The recorded execution of an invocation.
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    /// The color written by the invocation, unless discarded.
+    pub output: Option<[f32; 4]>,
+    pub discarded: bool,
+    /// Whether steps were left unrecorded past [`MAX_STEPS`].
+    pub truncated: bool,
+}
+
+/// The state of the invocation right before a statement is executed.
+#[derive(Debug, Clone)]
+pub struct Step {
+    /// The call stack, the innermost function last.
+    pub frames: Vec<Frame>,
+}
+
+impl Step {
+    /// The line of the statement about to be executed.
+    pub fn line(&self) -> usize {
+        self.frames
+            .last()
+            .map(|frame| frame.line)
+            .unwrap_or_default()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub function: String,
+    pub line: usize,
+    /// The named local variables, once assigned.
+    pub locals: Vec<(String, Value)>,
+    /// The loops being executed, the innermost last.
+    pub loops: Vec<Loop>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Loop {
+    pub line: usize,
+    /// The 1-based number of the iteration being executed.
+    pub iteration: usize,
+}
+
+impl Program {
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let (source, lines) = source::rewrite(source);
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        let module = glsl::Frontend::default()
+            .parse(&glsl::Options::from(naga::ShaderStage::Fragment), &source);
+
+        let mut program = Self {
+            module: Default::default(),
+            starts,
+            lines,
+        };
+
+        match module {
+            Ok(module) => {
+                program.module = module;
+
+                Ok(program)
+            }
+            Err(errors) => Err(Error::Interpret(
+                errors
+                    .iter()
+                    .map(|error| match program.line(error.meta) {
+                        0 => error.kind.to_string(),
+                        line => format!("Line {line}: {}", error.kind),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )),
+        }
+    }
+
+    /// The original line of the code at `span`, or `0` if unknown.
+    fn line(&self, span: Span) -> usize {
+        let Some(range) = span.to_range() else {
+            return 0;
+        };
+        let line = match self.starts.binary_search(&range.start) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        self.lines.get(line).copied().unwrap_or_default()
+    }
+
+    /// Interpret the shader for a single pixel.
+    pub fn run(&self, inputs: &Inputs) -> Result<Trace, Error> {
+        let entry = self
+            .module
+            .entry_points
+            .first()
+            .ok_or_else(|| Error::Interpret("No `main` function in the shader".into()))?;
+
+        let mut machine = Machine {
+            program: self,
+            inputs,
+            globals: Vec::new(),
+            frames: Vec::new(),
+            statements: 0,
+            trace: Trace::default(),
+        };
+
+        machine.run(entry).map_err(|err| {
+            let line = machine
+                .frames
+                .last()
+                .map(|frame| frame.line)
+                .unwrap_or_default();
+
+            Error::Interpret(format!("Line {line}: {err}"))
+        })?;
+
+        Ok(machine.trace)
+    }
+}
+
+/// The control flow resulting from the execution of a statement.
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Value>),
+    Kill,
+}
+
+/// A function being executed.
+struct Activation<'a> {
+    function: &'a Function,
+    name: String,
+    line: usize,
+    arguments: Vec<Value>,
+    locals: Vec<Value>,
+    /// Whether each local variable was assigned yet.
+    assigned: Vec<bool>,
+    /// The values of the evaluated expressions.
+    values: Vec<Option<Value>>,
+    loops: Vec<Loop>,
+}
+
+impl Activation<'_> {
+    fn frame(&self) -> Frame {
+        Frame {
+            function: self.name.clone(),
+            line: self.line,
+            locals: self
+                .function
+                .local_variables
+                .iter()
+                .filter(|(handle, _)| self.assigned[handle.index()])
+                .filter_map(|(handle, local)| {
+                    Some((local.name.clone()?, self.locals[handle.index()].clone()))
+                })
+                .collect(),
+            loops: self.loops.clone(),
+        }
+    }
+}
+
+struct Machine<'a> {
+    program: &'a Program,
+    inputs: &'a Inputs,
+    globals: Vec<Value>,
+    frames: Vec<Activation<'a>>,
+    statements: usize,
+    trace: Trace,
+}
+
+fn literal(literal: &Literal) -> Scalar {
+    match *literal {
+        Literal::F64(value) => Scalar::Float(value as f32),
+        Literal::F32(value) => Scalar::Float(value),
+        Literal::U32(value) => Scalar::Uint(value),
+        Literal::I32(value) => Scalar::Sint(value),
+        Literal::Bool(value) => Scalar::Bool(value),
+    }
+}
+
+fn zero(kind: ScalarKind) -> Scalar {
+    match kind {
+        ScalarKind::Float => Scalar::Float(0.0),
+        ScalarKind::Sint => Scalar::Sint(0),
+        ScalarKind::Uint => Scalar::Uint(0),
+        ScalarKind::Bool => Scalar::Bool(false),
+    }
+}
+
+impl<'a> Machine<'a> {
+    fn run(&mut self, entry: &'a naga::EntryPoint) -> Result<(), String> {
+        let module = &self.program.module;
+
+        self.globals = module
+            .global_variables
+            .iter()
+            .map(|(_, global)| self.global(global))
+            .collect::<Result<_, _>>()?;
+
+        let coord = self.inputs.coord;
+        let arguments = entry
+            .function
+            .arguments
+            .iter()
+            .map(|argument| match argument.binding {
+                Some(Binding::BuiltIn(BuiltIn::Position { .. })) => {
+                    Ok(Value::from_floats([coord[0], coord[1], 0.5, 1.0]))
+                }
+                Some(Binding::BuiltIn(BuiltIn::FrontFacing)) => {
+                    Ok(Value::Scalar(Scalar::Bool(true)))
+                }
+                _ => self.zero(argument.ty),
+            })
+            .collect::<Result<_, _>>()?;
+
+        match self.call(&entry.function, entry.name.clone(), arguments)? {
+            Flow::Kill => self.trace.discarded = true,
+            Flow::Return(Some(value)) => self.trace.output = self.output(&entry.function, &value),
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Extract the color written to the first output of the shader from the entry point result.
+    fn output(&self, function: &Function, value: &Value) -> Option<[f32; 4]> {
+        let result = function.result.as_ref()?;
+        let value = match (&result.binding, &self.program.module.types[result.ty].inner) {
+            (None, TypeInner::Struct { members, .. }) => value
+                .at(members.iter().position(|member| {
+                    matches!(member.binding, Some(Binding::Location { location: 0, .. }))
+                })?)
+                .ok()?,
+            _ => value.clone(),
+        };
+        let floats = value.floats().ok()?;
+
+        Some([0, 1, 2, 3].map(|i| {
+            floats
+                .get(i)
+                .copied()
+                .unwrap_or(if i == 3 { 1.0 } else { 0.0 })
+        }))
+    }
+
+    fn global(&self, global: &GlobalVariable) -> Result<Value, String> {
+        let name = global.name.clone().unwrap_or_default();
+
+        match global.space {
+            AddressSpace::Handle => Ok(Value::Handle(name)),
+            AddressSpace::Uniform | AddressSpace::PushConstant => {
+                let zero = self.zero(global.ty)?;
+
+                match self.inputs.uniforms.get(&name) {
+                    Some(value) if value.len() == zero.components().map_or(0, |c| c.len()) => {
+                        convert(&Value::from_floats(value.iter().copied()), &zero)
+                    }
+                    _ => Ok(zero),
+                }
+            }
+            _ => match global.init {
+                Some(init) => self.constant(init),
+                None => self.zero(global.ty),
+            },
+        }
+    }
+
+    fn zero(&self, ty: Handle<Type>) -> Result<Value, String> {
+        Ok(match &self.program.module.types[ty].inner {
+            TypeInner::Scalar { kind, .. } => Value::Scalar(zero(*kind)),
+            TypeInner::Vector { size, kind, .. } => {
+                Value::Vector(vec![zero(*kind); *size as usize])
+            }
+            TypeInner::Matrix { columns, rows, .. } => {
+                Value::Matrix(vec![vec![0.0; *rows as usize]; *columns as usize])
+            }
+            TypeInner::Array {
+                base,
+                size: ArraySize::Constant(size),
+                ..
+            } => Value::Composite(vec![self.zero(*base)?; size.get() as usize]),
+            TypeInner::Struct { members, .. } => Value::Composite(
+                members
+                    .iter()
+                    .map(|member| self.zero(member.ty))
+                    .collect::<Result<_, _>>()?,
+            ),
+            other => return Err(format!("Unsupported type `{other:?}`")),
+        })
+    }
+
+    fn compose(&self, ty: Handle<Type>, components: Vec<Value>) -> Result<Value, String> {
+        Ok(match &self.program.module.types[ty].inner {
+            TypeInner::Vector { .. } => Value::Vector(
+                components
+                    .iter()
+                    .map(Value::components)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .concat(),
+            ),
+            TypeInner::Matrix { .. } => Value::Matrix(
+                components
+                    .iter()
+                    .map(Value::floats)
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => Value::Composite(components),
+        })
+    }
+
+    /// Evaluate an expression of the module's constant expressions.
+    fn constant(&self, handle: Handle<Expression>) -> Result<Value, String> {
+        let module = &self.program.module;
+
+        match &module.const_expressions[handle] {
+            Expression::Literal(value) => Ok(Value::Scalar(literal(value))),
+            Expression::Constant(constant) => self.constant(module.constants[*constant].init),
+            Expression::ZeroValue(ty) => self.zero(*ty),
+            Expression::Compose { ty, components } => self.compose(
+                *ty,
+                components
+                    .iter()
+                    .map(|component| self.constant(*component))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expression::Splat { size, value } => Ok(Value::Vector(vec![
+                self.constant(*value)?
+                    .scalar()?;
+                *size as usize
+            ])),
+            other => Err(format!("Unsupported constant expression `{other:?}`")),
+        }
+    }
+
+    fn frame(&self) -> &Activation<'a> {
+        self.frames.last().expect("No function being executed")
+    }
+
+    fn frame_mut(&mut self) -> &mut Activation<'a> {
+        self.frames.last_mut().expect("No function being executed")
+    }
+
+    fn call(
+        &mut self,
+        function: &'a Function,
+        name: String,
+        arguments: Vec<Value>,
+    ) -> Result<Flow, String> {
+        if self.frames.len() >= MAX_DEPTH {
+            return Err("Maximum call depth exceeded".into());
+        }
+
+        let locals = function
+            .local_variables
+            .iter()
+            .map(|(_, local)| self.zero(local.ty))
+            .collect::<Result<_, _>>()?;
+
+        self.frames.push(Activation {
+            function,
+            name,
+            line: 0,
+            arguments,
+            locals,
+            assigned: vec![false; function.local_variables.len()],
+            values: vec![None; function.expressions.len()],
+            loops: Vec::new(),
+        });
+
+        for (handle, local) in function.local_variables.iter() {
+            if let Some(init) = local.init {
+                let value = self.value(init)?;
+
+                let frame = self.frame_mut();
+                frame.locals[handle.index()] = value;
+                frame.assigned[handle.index()] = true;
+            }
+        }
+
+        let flow = self.block(&function.body)?;
+        self.frames.pop();
+
+        Ok(flow)
+    }
+
+    /// Record the state of the invocation before executing the statement at `span`.
+    fn record(&mut self, span: Span) -> Result<(), String> {
+        self.statements += 1;
+        if self.statements > MAX_STATEMENTS {
+            return Err(format!(
+                "Stopped after {MAX_STATEMENTS} statements, is there an infinite loop?"
+            ));
+        }
+
+        let line = self.program.line(span);
+        // Skip the entry point generated by naga, which calls the shader's `main`
+        if line == 0 || self.frames.len() < 2 {
+            return Ok(());
+        }
+
+        self.frame_mut().line = line;
+
+        if self.trace.steps.len() < MAX_STEPS {
+            let frames = self.frames[1..].iter().map(Activation::frame).collect();
+
+            self.trace.steps.push(Step { frames });
+        } else {
+            self.trace.truncated = true;
+        }
+
+        Ok(())
+    }
+
+    fn block(&mut self, block: &'a Block) -> Result<Flow, String> {
+        for (statement, span) in block.span_iter() {
+            if !matches!(statement, Statement::Emit(_) | Statement::Block(_)) {
+                self.record(*span)?;
+            }
+
+            match self.statement(statement, *span)? {
+                Flow::Next => (),
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn statement(&mut self, statement: &'a Statement, span: Span) -> Result<Flow, String> {
+        let program = self.program;
+
+        match statement {
+            Statement::Emit(range) => {
+                for handle in range.clone() {
+                    let value = self.evaluate(handle)?;
+
+                    self.frame_mut().values[handle.index()] = Some(value);
+                }
+            }
+            Statement::Block(block) => return self.block(block),
+            Statement::If {
+                condition,
+                accept,
+                reject,
+            } => {
+                return if self.value(*condition)?.scalar()?.bool()? {
+                    self.block(accept)
+                } else {
+                    self.block(reject)
+                };
+            }
+            Statement::Switch { selector, cases } => {
+                let selector = self.value(*selector)?.scalar()?.index()?;
+                let start = cases
+                    .iter()
+                    .position(|case| match case.value {
+                        SwitchValue::I32(value) => value as i64 == selector,
+                        SwitchValue::U32(value) => value as i64 == selector,
+                        SwitchValue::Default => false,
+                    })
+                    .or_else(|| {
+                        cases
+                            .iter()
+                            .position(|case| matches!(case.value, SwitchValue::Default))
+                    });
+
+                if let Some(start) = start {
+                    for case in &cases[start..] {
+                        match self.block(&case.body)? {
+                            Flow::Next if case.fall_through => continue,
+                            Flow::Next | Flow::Break => break,
+                            flow => return Ok(flow),
+                        }
+                    }
+                }
+            }
+            Statement::Loop {
+                body,
+                continuing,
+                break_if,
+            } => {
+                let line = self.program.line(span);
+                self.frame_mut().loops.push(Loop { line, iteration: 0 });
+
+                let flow = loop {
+                    if let Some(current) = self.frame_mut().loops.last_mut() {
+                        current.iteration += 1;
+                    }
+
+                    match self.block(body)? {
+                        Flow::Next | Flow::Continue => (),
+                        Flow::Break => break Flow::Next,
+                        flow => break flow,
+                    }
+                    match self.block(continuing)? {
+                        Flow::Next | Flow::Continue => (),
+                        Flow::Break => break Flow::Next,
+                        flow => break flow,
+                    }
+
+                    if let Some(condition) = break_if {
+                        if self.value(*condition)?.scalar()?.bool()? {
+                            break Flow::Next;
+                        }
+                    }
+                };
+
+                self.frame_mut().loops.pop();
+
+                return Ok(flow);
+            }
+            Statement::Break => return Ok(Flow::Break),
+            Statement::Continue => return Ok(Flow::Continue),
+            Statement::Return { value } => {
+                return Ok(Flow::Return(
+                    value.map(|value| self.value(value)).transpose()?,
+                ));
+            }
+            Statement::Kill => return Ok(Flow::Kill),
+            Statement::Barrier(_) => (),
+            Statement::Store { pointer, value } => {
+                let pointer = self.value(*pointer)?;
+                let value = self.value(*value)?;
+
+                self.store(pointer.pointer()?, value)?;
+            }
+            Statement::Call {
+                function,
+                arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.value(*argument))
+                    .collect::<Result<_, _>>()?;
+                let callee = &program.module.functions[*function];
+
+                match self.call(callee, callee.name.clone().unwrap_or_default(), arguments)? {
+                    Flow::Return(Some(value)) => {
+                        if let Some(result) = result {
+                            self.frame_mut().values[result.index()] = Some(value);
+                        }
+                    }
+                    Flow::Kill => return Ok(Flow::Kill),
+                    _ => (),
+                }
+            }
+            other => return Err(format!("Unsupported statement `{other:?}`")),
+        }
+
+        Ok(Flow::Next)
+    }
+
+    /// The value of an expression, evaluated if it wasn't already.
+    fn value(&mut self, handle: Handle<Expression>) -> Result<Value, String> {
+        if let Some(value) = &self.frame().values[handle.index()] {
+            return Ok(value.clone());
+        }
+
+        let value = self.evaluate(handle)?;
+        self.frame_mut().values[handle.index()] = Some(value.clone());
+
+        Ok(value)
+    }
+
+    fn evaluate(&mut self, handle: Handle<Expression>) -> Result<Value, String> {
+        let program = self.program;
+        let function = self.frame().function;
+
+        match &function.expressions[handle] {
+            Expression::Literal(value) => Ok(Value::Scalar(literal(value))),
+            Expression::Constant(constant) => {
+                self.constant(program.module.constants[*constant].init)
+            }
+            Expression::ZeroValue(ty) => self.zero(*ty),
+            Expression::Compose { ty, components } => {
+                let components = components
+                    .iter()
+                    .map(|component| self.value(*component))
+                    .collect::<Result<_, _>>()?;
+
+                self.compose(*ty, components)
+            }
+            Expression::Access { base, index } => {
+                let index = self.value(*index)?.scalar()?.index()?;
+
+                self.access(
+                    *base,
+                    usize::try_from(index).map_err(|_| format!("Negative index {index}"))?,
+                )
+            }
+            Expression::AccessIndex { base, index } => self.access(*base, *index as usize),
+            Expression::Splat { size, value } => Ok(Value::Vector(vec![
+                self.value(*value)?
+                    .scalar()?;
+                *size as usize
+            ])),
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let components = self.value(*vector)?.components()?;
+
+                Ok(Value::from_components(
+                    pattern[..*size as usize]
+                        .iter()
+                        .map(|component| {
+                            components
+                                .get(*component as usize)
+                                .copied()
+                                .ok_or("Swizzle out of the vector")
+                        })
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            Expression::FunctionArgument(index) => self
+                .frame()
+                .arguments
+                .get(*index as usize)
+                .cloned()
+                .ok_or_else(|| format!("Missing function argument {index}")),
+            Expression::GlobalVariable(global) => {
+                match program.module.global_variables[*global].space {
+                    AddressSpace::Handle => Ok(self.globals[global.index()].clone()),
+                    _ => Ok(Value::Pointer(Pointer {
+                        root: Root::Global(global.index()),
+                        path: Vec::new(),
+                    })),
+                }
+            }
+            Expression::LocalVariable(local) => Ok(Value::Pointer(Pointer {
+                root: Root::Local {
+                    frame: self.frames.len() - 1,
+                    local: local.index(),
+                },
+                path: Vec::new(),
+            })),
+            Expression::Load { pointer } => {
+                let pointer = self.value(*pointer)?;
+
+                self.variable(pointer.pointer()?.root)?
+                    .get(&pointer.pointer()?.path)
+            }
+            Expression::ImageSample {
+                image,
+                coordinate,
+                gather: None,
+                ..
+            } => {
+                let coordinate = self.value(*coordinate)?.floats()?;
+                let image = self.value(*image)?;
+                let texture = self.texture(&image);
+
+                Ok(Value::from_floats(match (texture, coordinate.as_slice()) {
+                    (Some(texture), [u, v, ..]) => texture.sample(*u, *v),
+                    _ => [0.0, 0.0, 0.0, 1.0],
+                }))
+            }
+            Expression::ImageLoad {
+                image, coordinate, ..
+            } => {
+                let coordinate = self
+                    .value(*coordinate)?
+                    .components()?
+                    .into_iter()
+                    .map(Scalar::index)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let image = self.value(*image)?;
+                let texture = self.texture(&image);
+
+                Ok(Value::from_floats(match (texture, coordinate.as_slice()) {
+                    (Some(texture), [x, y, ..]) => texture.fetch(*x, *y),
+                    _ => [0.0; 4],
+                }))
+            }
+            Expression::ImageQuery {
+                image,
+                query: ImageQuery::Size { .. },
+            } => {
+                let image = self.value(*image)?;
+                let texture = self.texture(&image);
+                let (width, height) = texture
+                    .map(|texture| (texture.width, texture.height))
+                    .unwrap_or_default();
+
+                Ok(Value::Vector(vec![
+                    Scalar::Uint(width as u32),
+                    Scalar::Uint(height as u32),
+                ]))
+            }
+            Expression::Unary { op, expr } => math::unary(*op, &self.value(*expr)?),
+            Expression::Binary { op, left, right } => {
+                let left = self.value(*left)?;
+                let right = self.value(*right)?;
+
+                math::binary(*op, &left, &right)
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.value(*condition)?;
+                let accept = self.value(*accept)?;
+                let reject = self.value(*reject)?;
+
+                match condition {
+                    Value::Scalar(condition) if condition.bool()? => Ok(accept),
+                    Value::Scalar(_) => Ok(reject),
+                    _ => math::componentwise(&[&condition, &accept, &reject], |x| {
+                        Ok(if x[0].bool()? { x[1] } else { x[2] })
+                    }),
+                }
+            }
+            // Derivatives are unavailable for a single invocation
+            Expression::Derivative { expr, .. } => {
+                math::componentwise(&[&self.value(*expr)?], |x| Ok(x[0].zero()))
+            }
+            Expression::Relational { fun, argument } => {
+                math::relational(*fun, &self.value(*argument)?)
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let args = [Some(*arg), *arg1, *arg2, *arg3]
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| self.value(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                math::math(*fun, &args.iter().collect::<Vec<_>>())
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => math::convert(&self.value(*expr)?, *kind, convert.is_some()),
+            Expression::CallResult(_) => Err("Function result used before the call".into()),
+            other => Err(format!("Unsupported expression `{other:?}`")),
+        }
+    }
+
+    fn access(&mut self, base: Handle<Expression>, index: usize) -> Result<Value, String> {
+        match self.value(base)? {
+            Value::Pointer(mut pointer) => {
+                pointer.path.push(index);
+
+                Ok(Value::Pointer(pointer))
+            }
+            value => value.at(index),
+        }
+    }
+
+    fn texture(&self, image: &Value) -> Option<&'a Texture> {
+        let Value::Handle(name) = image else {
+            return None;
+        };
+
+        self.inputs
+            .textures
+            .get(name.strip_suffix(source::TEXTURE)?)
+    }
+
+    fn variable(&self, root: Root) -> Result<&Value, String> {
+        match root {
+            Root::Global(global) => self.globals.get(global),
+            Root::Local { frame, local } => self
+                .frames
+                .get(frame)
+                .and_then(|frame| frame.locals.get(local)),
+        }
+        .ok_or_else(|| "Dangling pointer".into())
+    }
+
+    fn store(&mut self, pointer: &Pointer, value: Value) -> Result<(), String> {
+        let variable = match pointer.root {
+            Root::Global(global) => self.globals.get_mut(global),
+            Root::Local { frame, local } => self.frames.get_mut(frame).and_then(|frame| {
+                *frame.assigned.get_mut(local)? = true;
+
+                frame.locals.get_mut(local)
+            }),
+        };
+
+        variable
+            .ok_or("Dangling pointer")?
+            .set(&pointer.path, value)
+    }
+}
+
+/// Convert the float components of `value` to the kinds of the components of `like`.
+fn convert(value: &Value, like: &Value) -> Result<Value, String> {
+    let kinds = like.components()?;
+    let components = value
+        .components()?
+        .into_iter()
+        .zip(kinds)
+        .map(|(x, kind)| {
+            let x = x.float()?;
+
+            Ok(match kind {
+                Scalar::Float(_) => Scalar::Float(x),
+                Scalar::Sint(_) => Scalar::Sint(x as i32),
+                Scalar::Uint(_) => Scalar::Uint(x as u32),
+                Scalar::Bool(_) => Scalar::Bool(x != 0.0),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(Value::from_components(components))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interpret `source` for the pixel at `coord`.
+    fn run(source: &str, coord: [f32; 2]) -> Trace {
+        let program = Program::parse(source).expect("Unable to parse the shader");
+
+        program
+            .run(&Inputs {
+                coord,
+                ..Default::default()
+            })
+            .expect("Unable to interpret the shader")
+    }
+
+    /// The last value of the local `name` of the innermost function, as seen by the following steps.
+    fn local(trace: &Trace, name: &str) -> Value {
+        trace
+            .steps
+            .iter()
+            .rev()
+            .filter_map(|step| step.frames.last())
+            .find_map(|frame| {
+                frame
+                    .locals
+                    .iter()
+                    .find(|(local, _)| local == name)
+                    .map(|(_, value)| value.clone())
+            })
+            .unwrap_or_else(|| panic!("No local `{name}` was assigned"))
+    }
+
+    fn assert_output(trace: &Trace, expected: [f32; 4]) {
+        let output = trace.output.expect("No color was output");
+
+        for (output, expected) in output.iter().zip(expected) {
+            assert!(
+                (output - expected).abs() < 1e-5,
+                "Output {output:?} differs from {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn output() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                color = vec4(gl_FragCoord.xy / vec2(4.0), 0.25, 1.0);
+            }",
+            [1.5, 2.5],
+        );
+
+        assert_output(&trace, [0.375, 0.625, 0.25, 1.0]);
+        assert!(!trace.discarded);
+    }
+
+    #[test]
+    fn builtins() {
+        // Derived from the coordinates so that naga can't fold them
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                float x = gl_FragCoord.x;
+                color = vec4(mod(x - 2.0, 1.0), fract(-x), smoothstep(0.0, 1.0, x), step(x, 0.25));
+            }",
+            [0.5, 0.5],
+        );
+
+        assert_output(&trace, [0.5, 0.5, 0.5, 0.0]);
+
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                float x = gl_FragCoord.x;
+                color = vec4(clamp(x * 4.0, 0.0, 1.0), mix(1.0, 3.0, x), length(vec2(x * 6.0, x * 8.0)), sign(-x));
+            }",
+            [0.5, 0.5],
+        );
+
+        assert_output(&trace, [1.0, 2.0, 5.0, -1.0]);
+    }
+
+    #[test]
+    fn integers() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                int n = int(gl_FragCoord.x);
+                int quotient = -n / 2;
+                int remainder = n % 4;
+                uint bits = uint(n) << 2u;
+                color = vec4(0.0);
+            }",
+            [7.5, 0.5],
+        );
+
+        // Integer division truncates towards zero
+        assert_eq!(local(&trace, "quotient"), Value::Scalar(Scalar::Sint(-3)));
+        assert_eq!(local(&trace, "remainder"), Value::Scalar(Scalar::Sint(3)));
+        assert_eq!(local(&trace, "bits"), Value::Scalar(Scalar::Uint(28)));
+    }
+
+    #[test]
+    fn swizzles() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                vec4 v = vec4(1.0, 2.0, 3.0, 4.0) * gl_FragCoord.x;
+                v.xw = v.wx;
+                vec3 w = v.zyx;
+                color = vec4(w, v.x);
+            }",
+            [1.0, 0.5],
+        );
+
+        assert_eq!(local(&trace, "v"), Value::from_floats([4.0, 2.0, 3.0, 1.0]));
+        assert_output(&trace, [3.0, 2.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn control_flow() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                int sum = 0;
+                for (int i = 0; i < int(gl_FragCoord.x); i++) {
+                    if (i == 2) {
+                        continue;
+                    }
+                    sum += i;
+                }
+                color = vec4(float(sum));
+            }",
+            [5.5, 0.5],
+        );
+
+        assert_output(&trace, [8.0; 4]);
+    }
+
+    #[test]
+    fn discard() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                if (gl_FragCoord.x > 1.0) {
+                    discard;
+                }
+                color = vec4(1.0);
+            }",
+            [2.5, 0.5],
+        );
+
+        assert!(trace.discarded);
+        assert!(trace.output.is_none());
+    }
+
+    #[test]
+    fn steps() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            float twice(float x) {
+                return x * 2.0;
+            }
+            void main() {
+                float a = gl_FragCoord.x;
+                float b = twice(a);
+                color = vec4(b);
+            }",
+            [1.5, 0.5],
+        );
+
+        let lines = trace.steps.iter().map(Step::line).collect::<Vec<_>>();
+        assert_eq!(lines.first(), Some(&7));
+        assert!(lines.contains(&4));
+        assert_eq!(lines.last(), Some(&9));
+
+        // The call stack while in `twice`, with its argument
+        let step = trace
+            .steps
+            .iter()
+            .find(|step| step.line() == 4)
+            .expect("No step in `twice`");
+        let functions = step
+            .frames
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(functions, ["main", "twice"]);
+        assert_eq!(step.frames[0].line, 8);
+
+        assert_eq!(local(&trace, "b"), Value::Scalar(Scalar::Float(3.0)));
+        assert_output(&trace, [3.0; 4]);
+    }
+
+    #[test]
+    fn loops() {
+        let trace = run(
+            "#version 330 core
+            out vec4 color;
+            void main() {
+                float sum = 0.0;
+                for (int i = 0; i < 3; i++) {
+                    sum += 1.0;
+                }
+                color = vec4(sum);
+            }",
+            [0.5, 0.5],
+        );
+
+        let iterations = trace
+            .steps
+            .iter()
+            .filter(|step| step.line() == 6)
+            .map(|step| {
+                step.frames[0]
+                    .loops
+                    .last()
+                    .expect("Not in a loop")
+                    .iteration
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(iterations, [1, 2, 3]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Program::parse("#version 330 core\nvoid main() {\n    undefined = 1.0;\n}"),
+            Err(Error::Interpret(err)) if err.contains("Line 3")
+        ));
+    }
+}
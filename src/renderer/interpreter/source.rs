@@ -0,0 +1,116 @@
+//! Rewriting of the user's fragment shader into GLSL 4.50 accepted by naga's frontend,
+//! keeping track of the original line of each rewritten line.
+
+use super::super::instrument;
+
+/// The suffixes of the separate texture and sampler a `sampler2D` uniform is split into.
+pub const TEXTURE: &str = "_glou_texture";
+pub const SAMPLER: &str = "_glou_sampler";
+
+/// The shader output replacing `gl_FragColor`.
+const OUTPUT: &str = "_glou_FragColor";
+
+/// Rewrite `source` and return it alongside the original 1-based line of each line, `0` for inserted ones.
+pub fn rewrite(source: &str) -> (String, Vec<usize>) {
+    let mut source = instrument::strip_comments(source);
+
+    let output = instrument::output(&source);
+    if output == "gl_FragColor" {
+        source = instrument::rename(&source, "gl_FragColor", OUTPUT);
+    }
+    source = instrument::rename(&source, "texture2D", "texture");
+
+    let mut rewritten = String::with_capacity(source.len());
+    let mut lines = Vec::new();
+    let mut push = |line: &str, number: usize| {
+        rewritten.push_str(line);
+        rewritten.push('\n');
+        lines.push(number);
+    };
+
+    let mut source = source.lines().enumerate().peekable();
+
+    // The `#version` directive must stay the first line of the source
+    match source.next_if(|(_, line)| line.trim_start().starts_with("#version")) {
+        Some((number, _)) => push("#version 450", number + 1),
+        None => push("#version 450", 0),
+    }
+    if output == "gl_FragColor" {
+        push(&format!("layout(location = 0) out vec4 {OUTPUT};"), 0);
+    }
+
+    let mut binding = 0;
+    for (number, line) in source {
+        match uniform(line) {
+            Some((_, "sampler2D", names)) => {
+                let mut declarations = String::new();
+                let mut defines = Vec::new();
+
+                for name in names {
+                    declarations.push_str(&format!(
+                        "layout(binding = {}) uniform texture2D {name}{TEXTURE}; \
+                         layout(binding = {}) uniform sampler {name}{SAMPLER}; ",
+                        binding,
+                        binding + 1
+                    ));
+                    defines.push(format!(
+                        "#define {name} sampler2D({name}{TEXTURE}, {name}{SAMPLER})"
+                    ));
+
+                    binding += 2;
+                }
+
+                push(&declarations, number + 1);
+                for define in defines {
+                    push(&define, number + 1);
+                }
+            }
+            Some((layout, _, _)) => {
+                let qualifiers = match layout {
+                    Some(layout) => format!("binding = {binding}, {layout}"),
+                    None => format!("binding = {binding}"),
+                };
+                let declaration = &line[line.find("uniform").unwrap_or_default()..];
+
+                push(&format!("layout({qualifiers}) {declaration}"), number + 1);
+
+                binding += 1;
+            }
+            None => push(line, number + 1),
+        }
+    }
+
+    (rewritten, lines)
+}
+
+/// Parse a global `uniform` declaration into its layout qualifiers, type and declared names.
+fn uniform(line: &str) -> Option<(Option<&str>, &str, Vec<&str>)> {
+    let line = line.trim();
+
+    let (layout, rest) = match line.strip_prefix("layout") {
+        Some(rest) => {
+            let rest = rest.trim_start().strip_prefix('(')?;
+            let end = rest.find(')')?;
+
+            (Some(rest[..end].trim()), rest[end + 1..].trim_start())
+        }
+        None => (None, line),
+    };
+
+    let rest = rest.strip_prefix("uniform")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let mut tokens = rest
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|token| !token.is_empty())
+        .filter(|token| !matches!(*token, "lowp" | "mediump" | "highp"));
+
+    let ty = tokens.next()?;
+    let names = tokens
+        .take_while(|token| token.chars().all(instrument::is_identifier))
+        .collect();
+
+    Some((layout, ty, names))
+}
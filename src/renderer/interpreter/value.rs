@@ -0,0 +1,227 @@
+use std::fmt;
+
+/// A single scalar value, doubles being narrowed to `float`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scalar {
+    Float(f32),
+    Sint(i32),
+    Uint(u32),
+    Bool(bool),
+}
+
+impl Scalar {
+    pub fn float(self) -> Result<f32, String> {
+        match self {
+            Self::Float(value) => Ok(value),
+            other => Err(format!("Expected a float, found `{other}`")),
+        }
+    }
+
+    pub fn bool(self) -> Result<bool, String> {
+        match self {
+            Self::Bool(value) => Ok(value),
+            other => Err(format!("Expected a boolean, found `{other}`")),
+        }
+    }
+
+    /// The value as an integer, for indexing and switch selectors.
+    pub fn index(self) -> Result<i64, String> {
+        match self {
+            Self::Sint(value) => Ok(value as i64),
+            Self::Uint(value) => Ok(value as i64),
+            other => Err(format!("Expected an integer, found `{other}`")),
+        }
+    }
+
+    /// The zero of the same kind as `self`.
+    pub fn zero(self) -> Self {
+        match self {
+            Self::Float(_) => Self::Float(0.0),
+            Self::Sint(_) => Self::Sint(0),
+            Self::Uint(_) => Self::Uint(0),
+            Self::Bool(_) => Self::Bool(false),
+        }
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(value) => write!(f, "{value:.4}"),
+            Self::Sint(value) => write!(f, "{value}"),
+            Self::Uint(value) => write!(f, "{value}u"),
+            Self::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// A value manipulated by the interpreter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Scalar(Scalar),
+    Vector(Vec<Scalar>),
+    /// A matrix, column by column.
+    Matrix(Vec<Vec<f32>>),
+    /// An array or a structure.
+    Composite(Vec<Value>),
+    /// A reference to a variable, or one of its components.
+    Pointer(Pointer),
+    /// A texture or sampler, by the name of its global variable.
+    Handle(String),
+}
+
+/// The variable a pointer refers to, and the path of component indices into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pointer {
+    pub root: Root,
+    pub path: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Root {
+    Global(usize),
+    Local { frame: usize, local: usize },
+}
+
+impl Value {
+    pub fn scalar(&self) -> Result<Scalar, String> {
+        match self {
+            Self::Scalar(scalar) => Ok(*scalar),
+            other => Err(format!("Expected a scalar, found `{other}`")),
+        }
+    }
+
+    /// The scalar components of a scalar or a vector.
+    pub fn components(&self) -> Result<Vec<Scalar>, String> {
+        match self {
+            Self::Scalar(scalar) => Ok(vec![*scalar]),
+            Self::Vector(components) => Ok(components.clone()),
+            other => Err(format!("Expected a scalar or vector, found `{other}`")),
+        }
+    }
+
+    /// The floating-point components of a scalar or a vector.
+    pub fn floats(&self) -> Result<Vec<f32>, String> {
+        self.components()?.into_iter().map(Scalar::float).collect()
+    }
+
+    pub fn pointer(&self) -> Result<&Pointer, String> {
+        match self {
+            Self::Pointer(pointer) => Ok(pointer),
+            other => Err(format!("Expected a pointer, found `{other}`")),
+        }
+    }
+
+    /// Build a scalar or a vector from its components.
+    pub fn from_components(mut components: Vec<Scalar>) -> Self {
+        if components.len() == 1 {
+            Self::Scalar(components.remove(0))
+        } else {
+            Self::Vector(components)
+        }
+    }
+
+    pub fn from_floats(components: impl IntoIterator<Item = f32>) -> Self {
+        Self::from_components(components.into_iter().map(Scalar::Float).collect())
+    }
+
+    /// The component at `index`, which is a column for matrices.
+    pub fn at(&self, index: usize) -> Result<Self, String> {
+        let component = match self {
+            Self::Vector(components) => components.get(index).copied().map(Self::Scalar),
+            Self::Matrix(columns) => columns
+                .get(index)
+                .map(|column| Self::from_floats(column.iter().copied())),
+            Self::Composite(members) => members.get(index).cloned(),
+            _ => None,
+        };
+
+        component.ok_or_else(|| format!("Unable to access component {index} of `{self}`"))
+    }
+
+    /// The component at the end of `path`.
+    pub fn get(&self, path: &[usize]) -> Result<Self, String> {
+        match path.split_first() {
+            Some((index, rest)) => self.at(*index)?.get(rest),
+            None => Ok(self.clone()),
+        }
+    }
+
+    /// Replace the component at the end of `path` with `value`.
+    pub fn set(&mut self, path: &[usize], value: Self) -> Result<(), String> {
+        let Some((index, rest)) = path.split_first() else {
+            *self = value;
+
+            return Ok(());
+        };
+
+        let error = || format!("Unable to write component {index}");
+        match self {
+            Self::Vector(components) if rest.is_empty() => {
+                *components.get_mut(*index).ok_or_else(error)? = value.scalar()?;
+            }
+            Self::Matrix(columns) => {
+                let column = columns.get_mut(*index).ok_or_else(error)?;
+                let mut current = Self::from_floats(column.iter().copied());
+
+                current.set(rest, value)?;
+                *column = current.floats()?;
+            }
+            Self::Composite(members) => {
+                members
+                    .get_mut(*index)
+                    .ok_or_else(error)?
+                    .set(rest, value)?;
+            }
+            _ => return Err(error()),
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{item}")?;
+            }
+
+            Ok(())
+        }
+
+        match self {
+            Self::Scalar(scalar) => write!(f, "{scalar}"),
+            Self::Vector(components) => {
+                write!(f, "(")?;
+                list(f, components)?;
+                write!(f, ")")
+            }
+            Self::Matrix(columns) => {
+                write!(f, "[")?;
+                for (index, column) in columns.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "(")?;
+                    list(
+                        f,
+                        &column.iter().map(|x| Scalar::Float(*x)).collect::<Vec<_>>(),
+                    )?;
+                    write!(f, ")")?;
+                }
+                write!(f, "]")
+            }
+            Self::Composite(members) => {
+                write!(f, "{{")?;
+                list(f, members)?;
+                write!(f, "}}")
+            }
+            Self::Pointer(_) => write!(f, "(pointer)"),
+            Self::Handle(name) => write!(f, "{name}"),
+        }
+    }
+}
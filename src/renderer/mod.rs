@@ -1,32 +1,156 @@
-use std::{rc::Rc, time};
-
-use eframe::{
-    egui,
-    glow::{self, HasContext},
-};
-
 mod shader;
 pub use shader::Shader;
 
+mod target;
+pub use target::{Format, Pool};
+
 mod uniforms;
-pub use uniforms::{UniformStyle, Uniforms};
+pub use uniforms::{Levels, UniformStyle, Uniforms};
+
+// The interface parts of the renderer, along with its inputs and outputs
+#[cfg(feature = "gui")]
+use std::rc::Rc;
+
+#[cfg(feature = "gui")]
+use eframe::{egui, glow};
+
+#[cfg(feature = "gui")]
+mod compare;
+#[cfg(feature = "gui")]
+pub use compare::{Comparison, Mode};
+
+#[cfg(feature = "gui")]
+mod debug;
+#[cfg(feature = "gui")]
+pub use debug::{Channel, Debugger};
+
+#[cfg(feature = "gui")]
+mod display;
+#[cfg(feature = "gui")]
+pub use display::{Display, Encoding, Tonemapper};
+
+#[cfg(feature = "gui")]
+mod heatmap;
+#[cfg(feature = "gui")]
+pub use heatmap::Heatmap;
+
+#[cfg(feature = "gui")]
+mod inspector;
+#[cfg(feature = "gui")]
+pub use inspector::{Inspection, Inspector};
+
+#[cfg(feature = "gui")]
+mod instrument;
+#[cfg(feature = "gui")]
+mod interpreter;
+#[cfg(feature = "gui")]
+mod navigation;
+#[cfg(feature = "gui")]
+pub use navigation::Navigation;
+
+#[cfg(feature = "gui")]
+mod ndi;
+#[cfg(feature = "gui")]
+pub use ndi::{Discovery, Receiver, Sender};
+
+#[cfg(feature = "gui")]
+mod output;
+#[cfg(feature = "gui")]
+pub use output::{Alpha, Feed, Output, PixelFormat, Scan};
+
+#[cfg(feature = "gui")]
+mod pass;
+
+#[cfg(feature = "gui")]
+mod plotter;
+#[cfg(feature = "gui")]
+pub use plotter::Plotter;
 
-use crate::canvas::Canvas;
+#[cfg(feature = "gui")]
+mod readback;
 
+#[cfg(feature = "gui")]
+mod reduce;
+
+#[cfg(feature = "gui")]
+mod sound;
+#[cfg(feature = "gui")]
+pub use sound::Sound;
+
+#[cfg(feature = "gui")]
+mod stepper;
+#[cfg(feature = "gui")]
+pub use stepper::Stepper;
+
+#[cfg(feature = "gui")]
+mod tab;
+#[cfg(feature = "gui")]
+use tab::Tab;
+#[cfg(feature = "gui")]
+pub use tab::{Errors, Session};
+
+#[cfg(feature = "gui")]
+mod timing;
+#[cfg(feature = "gui")]
+pub use timing::{Frame, Timings};
+
+#[cfg(feature = "gui")]
+mod widget;
+#[cfg(feature = "gui")]
+pub use widget::ShaderView;
+
+#[cfg(feature = "gui")]
+mod worker;
+#[cfg(feature = "gui")]
+pub use worker::{Connection, Worker};
+
+#[cfg(feature = "gui")]
+use crate::{
+    audio::Audio,
+    canvas::{Canvas, View},
+    midi::Midi,
+    AllocGuard, Error,
+};
+
+#[cfg(feature = "gui")]
 #[derive(Default)]
 pub struct Renderer {
     pub uniforms: Uniforms,
     pub shader: Option<Shader>,
-    pub buffer: Vec<u8>,
 
     pub size: egui::Vec2,
     pub resizable: bool,
+    pub format: Format,
+    pub display: Display,
+
+    pub outputs: Vec<Output>,
+    pub discovery: Option<Discovery>,
+    pub receiver: Option<Receiver>,
+
+    /// The display connection, to create the context of the [`Worker`].
+    pub connection: Option<Connection>,
+    pub worker: Option<Worker>,
+
+    pub midi: Midi,
+    pub audio: Option<Audio>,
+    pub sound: Option<Sound>,
 
-    pub ndi: Option<nndi::send::Send>,
-    pub ndi_name: String,
-    pub ndi_framerate: u8,
+    pub view: View,
+    pub navigation: Navigation,
+    pub inspector: Inspector,
+    pub debugger: Debugger,
+    pub stepper: Stepper,
+    pub heatmap: Heatmap,
+    pub comparison: Comparison,
+    pub plotter: Plotter,
+    pub timings: Timings,
+
+    /// The inactive tabs, around the position of the active one.
+    tabs: Vec<Tab>,
+    active: usize,
 }
 
+#[cfg(feature = "gui")]
 impl std::fmt::Debug for Renderer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Renderer")
@@ -34,17 +158,175 @@ impl std::fmt::Debug for Renderer {
             .field("shader", &self.shader)
             .field("size", &self.size)
             .field("resizable", &self.resizable)
-            .field("ndi", &())
-            .field("ndi_name", &self.ndi_name)
-            .field("ndi_framerate", &self.ndi_framerate)
+            .field("format", &self.format)
+            .field("display", &self.display)
+            .field("outputs", &self.outputs)
+            .field("discovery", &self.discovery)
+            .field("receiver", &self.receiver)
+            .field("connection", &self.connection)
+            .field("worker", &self.worker)
+            .field("midi", &self.midi)
+            .field("audio", &self.audio)
+            .field("sound", &self.sound)
+            .field("view", &self.view)
+            .field("navigation", &self.navigation)
+            .field("inspector", &self.inspector)
+            .field("debugger", &self.debugger)
+            .field("stepper", &self.stepper)
+            .field("heatmap", &self.heatmap)
+            .field("comparison", &self.comparison)
+            .field("plotter", &self.plotter)
+            .field("timings", &self.timings)
+            .field("tabs", &self.tabs)
+            .field("active", &self.active)
             .finish()
     }
 }
 
+#[cfg(feature = "gui")]
 impl Renderer {
+    /// The titles of the tabs, in order.
+    pub fn tabs(&self) -> Vec<String> {
+        let mut titles = self
+            .tabs
+            .iter()
+            .map(|tab| Tab::title(tab.shader.as_ref()))
+            .collect::<Vec<_>>();
+        titles.insert(self.active, Tab::title(self.shader.as_ref()));
+
+        titles
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Open a new empty tab and make it active, with `errors` the errors of the active tab.
+    pub fn open(&mut self, errors: &mut Errors) {
+        self.stash(errors);
+
+        self.active = self.tabs.len();
+        self.swap(&mut Tab::default(), errors);
+    }
+
+    /// Make the tab at `index` active, pausing the current one.
+    pub fn select(&mut self, index: usize, errors: &mut Errors) {
+        if index == self.active || index > self.tabs.len() {
+            return;
+        }
+
+        self.stash(errors);
+
+        let mut tab = self.tabs.remove(index);
+        self.swap(&mut tab, errors);
+        self.active = index;
+
+        // Resume the time where it was paused
+        self.uniforms.seek(self.uniforms.time());
+    }
+
+    /// Close the tab at `index`, activating a neighbouring tab if it was the active one.
+    pub fn close(&mut self, index: usize, errors: &mut Errors) {
+        if index > self.tabs.len() {
+            return;
+        }
+
+        match index.cmp(&self.active) {
+            std::cmp::Ordering::Less => {
+                self.tabs.remove(index);
+                self.active -= 1;
+            }
+            std::cmp::Ordering::Greater => {
+                self.tabs.remove(index - 1);
+            }
+            std::cmp::Ordering::Equal => {
+                let mut tab = match self.tabs.len() {
+                    0 => Tab::default(),
+                    len => {
+                        self.active = self.active.min(len - 1);
+                        self.tabs.remove(self.active)
+                    }
+                };
+
+                self.swap(&mut tab, errors);
+                self.uniforms.seek(self.uniforms.time());
+            }
+        }
+    }
+
+    /// The tabs to be persisted.
+    pub fn session(&mut self) -> Session {
+        let mut errors = Errors::default();
+
+        self.stash(&mut errors);
+        let session = Session::new(&self.tabs, self.active);
+
+        let mut tab = self.tabs.remove(self.active);
+        self.swap(&mut tab, &mut errors);
+
+        session
+    }
+
+    /// Replace the tabs with the persisted ones.
+    pub fn restore(&mut self, session: Session) {
+        if let Some((tabs, active)) = session.restore() {
+            self.tabs = tabs;
+            self.active = active;
+
+            let mut tab = self.tabs.remove(active);
+            self.swap(&mut tab, &mut Errors::default());
+        }
+    }
+
+    /// Move the state of the active tab into the inactive tabs, at its position.
+    fn stash(&mut self, errors: &mut Errors) {
+        let mut tab = Tab::default();
+
+        self.swap(&mut tab, errors);
+        self.tabs.insert(self.active, tab);
+    }
+
+    /// Compile and render the shader on a dedicated [`Worker`] thread, or back on the interface one.
+    pub fn set_threaded(&mut self, threaded: bool) -> Result<(), Error> {
+        self.worker = match (threaded, self.connection) {
+            (true, Some(connection)) => Some(Worker::new(connection)?),
+            _ => None,
+        };
+
+        // The shaders were only read while the worker compiled them
+        if self.worker.is_none() {
+            self.shader
+                .iter_mut()
+                .chain(self.tabs.iter_mut().filter_map(|tab| tab.shader.as_mut()))
+                .for_each(Shader::reset);
+        }
+
+        Ok(())
+    }
+
+    /// Exchange the state of the active tab with `tab`, along with its `errors`.
+    fn swap(&mut self, tab: &mut Tab, errors: &mut Errors) {
+        std::mem::swap(&mut self.shader, &mut tab.shader);
+        std::mem::swap(&mut self.uniforms, &mut tab.uniforms);
+        std::mem::swap(&mut self.size, &mut tab.size);
+        std::mem::swap(&mut self.resizable, &mut tab.resizable);
+        std::mem::swap(&mut self.format, &mut tab.format);
+        std::mem::swap(&mut self.display, &mut tab.display);
+        std::mem::swap(&mut self.view, &mut tab.view);
+        std::mem::swap(&mut self.navigation, &mut tab.navigation);
+        std::mem::swap(&mut self.inspector, &mut tab.inspector);
+        std::mem::swap(&mut self.debugger, &mut tab.debugger);
+        std::mem::swap(&mut self.stepper, &mut tab.stepper);
+        std::mem::swap(&mut self.heatmap, &mut tab.heatmap);
+        std::mem::swap(&mut self.comparison, &mut tab.comparison);
+        std::mem::swap(errors, &mut tab.errors);
+    }
+
     pub fn render_to_canvas(&mut self, gl: &Rc<glow::Context>, ui: &mut egui::Ui) -> Canvas {
-        let (response, painter) =
-            ui.allocate_painter(ui.available_size_before_wrap(), egui::Sense::hover());
+        let (response, painter) = ui.allocate_painter(
+            ui.available_size_before_wrap(),
+            egui::Sense::click_and_drag(),
+        );
         let viewport = egui::Rect {
             min: painter.round_pos_to_pixels(painter.clip_rect().min),
             max: painter.round_pos_to_pixels(painter.clip_rect().max),
@@ -54,94 +336,315 @@ impl Renderer {
             self.size = viewport.size();
         }
 
-        self.uniforms.update(
-            viewport,
+        let ppp = painter.ctx().pixels_per_point();
+
+        // Dragging the comparison split line takes over the other canvas interactions
+        let splitting = self.comparison.interact(
+            &response,
             response
-                .hover_pos()
-                .map(|pos| painter.round_pos_to_pixels(pos)),
+                .interact_pointer_pos()
+                .or(response.hover_pos())
+                .map(|pos| self.view.texture_pos(View::local(viewport, pos, ppp))),
+            viewport.width(),
         );
 
+        // The navigation takes over the canvas interactions when enabled
+        if !self.navigation.enabled && !splitting {
+            self.view.interact(ui, &response, viewport);
+        }
+
+        // The hovered position in the texture, in OpenGL coordinates
+        let hovered = response
+            .hover_pos()
+            .map(|pos| self.view.texture_pos(View::local(viewport, pos, ppp)));
+
+        if !splitting {
+            self.navigation
+                .interact(ui, &response, hovered, viewport.size(), self.view.zoom);
+        }
+        self.uniforms.set_navigation(self.navigation.uniforms());
+
+        self.uniforms
+            .update(viewport.size().into(), hovered.map(Into::into));
+        self.update_audio(gl);
+
+        let pixel = hovered.map(|pos| pos.floor());
+
+        if let (true, Some(pixel)) = (self.stepper.enabled && response.clicked(), pixel) {
+            self.stepper.select(pixel);
+        }
+
         let mut texture = None;
 
         if let Some(shader) = &self.shader {
             unsafe {
-                // Draw shader to right-sized texture
-                texture = Some(
-                    shader
-                        .render_to_texture(gl, &self.uniforms, viewport.size())
-                        .expect("Unable to render shader"),
-                );
+                // Draw shader to right-sized texture, or copy the latest frame of the worker,
+                // which timed its render on its own
+                let target = match &mut self.worker {
+                    Some(worker) => {
+                        let target = self.timings.measure(gl, "Copy", || {
+                            worker.render(gl, shader, &self.uniforms, viewport.size(), self.format)
+                        });
+                        if let Some(elapsed) = worker.elapsed() {
+                            self.timings.record("Shader", elapsed);
+                        }
+
+                        target
+                    }
+                    None => self.timings.measure(gl, "Shader", || {
+                        shader
+                            .render_to_texture(
+                                gl,
+                                &self.uniforms,
+                                viewport.size().into(),
+                                self.format,
+                            )
+                            .map(Some)
+                    }),
+                }
+                .and_then(|target| target);
+
+                let target = match target {
+                    Ok(Some(target)) => target,
+                    Ok(None) => return Canvas::new(None, painter, response, self.view),
+                    Err(err) => {
+                        tracing::warn!("Unable to render shader: {err}");
+
+                        return Canvas::new(None, painter, response, self.view);
+                    }
+                };
+
+                if let Err(err) = self.inspector.inspect(gl, *target, viewport.size(), pixel) {
+                    tracing::warn!("Unable to inspect pixel: {err}");
+                }
+
+                if let Err(err) = self.share(gl, *target, viewport.size()) {
+                    tracing::warn!("Unable to share render with outputs: {err}");
+                }
+
+                if let Err(err) =
+                    self.stepper
+                        .run(gl, &self.uniforms, *target, viewport.size(), self.format)
+                {
+                    tracing::warn!("Unable to interpret pixel: {err}");
+                }
+
+                let raw = *target;
+                let image = match self
+                    .timings
+                    .measure(gl, "Debug", || {
+                        self.debugger
+                            .render(gl, &self.uniforms, viewport.size(), pixel)
+                    })
+                    .and_then(|visualization| visualization)
+                {
+                    Ok(Some(visualization)) => visualization,
+                    result => {
+                        if let Err(err) = result {
+                            tracing::warn!("Unable to render debugged variant: {err}");
+                        }
+
+                        match self
+                            .timings
+                            .measure(gl, "Display", || {
+                                self.display.render(
+                                    gl,
+                                    *target,
+                                    viewport.size(),
+                                    self.display.canvas,
+                                    self.display.highlight,
+                                )
+                            })
+                            .and_then(|displayed| displayed)
+                        {
+                            Ok(Some(displayed)) => displayed,
+                            Ok(None) => target,
+                            Err(err) => {
+                                tracing::warn!("Unable to map render for display: {err}");
+
+                                target
+                            }
+                        }
+                    }
+                };
+
+                let image = match self
+                    .timings
+                    .measure(gl, "Comparison", || {
+                        self.comparison.render(
+                            gl,
+                            &self.uniforms,
+                            viewport.size(),
+                            self.format,
+                            &mut self.display,
+                            raw,
+                            *image,
+                        )
+                    })
+                    .and_then(|composed| composed)
+                {
+                    Ok(Some(composed)) => composed,
+                    Ok(None) => image,
+                    Err(err) => {
+                        tracing::warn!("Unable to render comparison: {err}");
+
+                        image
+                    }
+                };
+
+                texture = match self
+                    .timings
+                    .measure(gl, "Heatmap", || {
+                        self.heatmap
+                            .render(gl, &self.uniforms, viewport.size(), *image, pixel)
+                    })
+                    .and_then(|overlay| overlay)
+                {
+                    Ok(Some(overlay)) => Some(overlay),
+                    Ok(None) => Some(image),
+                    Err(err) => {
+                        tracing::warn!("Unable to render heatmap: {err}");
+
+                        Some(image)
+                    }
+                };
             };
         }
 
-        Canvas::new(texture, painter)
+        Canvas::new(texture, painter, response, self.view)
     }
 
-    fn render_to_buffer(&mut self, gl: &Rc<glow::Context>) -> Option<egui::Vec2> {
-        if let Some(shader) = &self.shader {
-            let viewport = egui::Rect::from_x_y_ranges(0.0..=self.size.x, 0.0..=self.size.y);
-            self.uniforms.update(viewport, None);
-
-            let texture = unsafe {
-                // Draw shader to right-sized texture
-                shader
-                    .render_to_texture(gl, &self.uniforms, viewport.size())
-                    .expect("Unable to render shader")
-            };
+    fn update_audio(&mut self, gl: &Rc<glow::Context>) {
+        let (levels, texture) = match &mut self.audio {
+            Some(audio) => match audio.update(gl, self.uniforms.time()) {
+                Ok((levels, texture)) => {
+                    (Some(levels), Some((texture, [Audio::WIDTH as f32, 2.0])))
+                }
+                Err(err) => {
+                    tracing::warn!("Unable to analyse audio: {err}");
+
+                    (None, None)
+                }
+            },
+            None => (None, None),
+        };
 
-            self.buffer.resize(
-                viewport.width() as usize * viewport.height() as usize * 4,
-                0,
-            );
+        self.uniforms.set_levels(levels);
+        self.uniforms.set_texture("audio", texture);
+    }
 
-            unsafe {
-                gl.bind_texture(glow::TEXTURE_2D, Some(*texture));
-                gl.get_tex_image(
-                    glow::TEXTURE_2D,
-                    0,
-                    glow::RGBA,
-                    glow::UNSIGNED_BYTE,
-                    glow::PixelPackData::Slice(&mut self.buffer),
-                );
-                gl.bind_texture(glow::TEXTURE_2D, None);
+    /// Submit the `target` rendered for the canvas at `size` to the ready outputs
+    /// of the shader at the same resolution, rather than rendering it again.
+    unsafe fn share(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        target: glow::Texture,
+        size: egui::Vec2,
+    ) -> Result<(), Error> {
+        for index in 0..self.outputs.len() {
+            let output = &self.outputs[index];
+            if !output.is_ready()
+                || output.feed == Feed::Comparison
+                || output.resolution(self.size) != size
+            {
+                continue;
+            }
+
+            let mapped = match output.feed {
+                Feed::Raw => None,
+                _ => self
+                    .display
+                    .render(gl, target, size, self.display.output, false)?,
             };
 
-            Some(viewport.size())
-        } else {
-            None
+            self.outputs[index].submit(
+                gl,
+                mapped.as_ref().map_or(target, |mapped| **mapped),
+                size,
+            )?;
         }
+
+        Ok(())
     }
 
-    // Reverse the Y-axis because it seems
-    // NDI expects origin to be top-left while OpenGL places it bottom-left
-    // let mut pixels = pixels
-    //     .chunks(viewport.width() as usize * 4)
-    //     .rev()
-    //     .flatten()
-    //     .copied()
-    //     .collect::<Vec<_>>();
-
-    pub fn send(&mut self, gl: &Rc<glow::Context>) {
-        if self.ndi.is_some() {
-            if let Some(size) = self.render_to_buffer(gl) {
-                if let Some(ndi) = &self.ndi {
-                    //ndi.send_video(&ndi::VideoData::from_buffer(
-                    //    size.x as i32,
-                    //    size.y as i32,
-                    //    ndi::FourCCVideoType::RGBA,
-                    //    self.ndi_framerate.into(),
-                    //    1,
-                    //    ndi::FrameFormatType::Progressive,
-                    //    time::SystemTime::now()
-                    //        .duration_since(time::UNIX_EPOCH)
-                    //        .expect("Time went backwards >.>")
-                    //        .as_secs() as i64,
-                    //    0,
-                    //    None,
-                    //    &mut self.buffer,
-                    //))
+    /// Render the `feed` at `size` for an output, if there is anything to render.
+    unsafe fn render_feed(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        feed: Feed,
+        size: egui::Vec2,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        let shader = match feed {
+            Feed::Shader | Feed::Raw => &self.shader,
+            Feed::Comparison => &self.comparison.shader,
+        };
+        let Some(shader) = shader else {
+            return Ok(None);
+        };
+
+        self.uniforms.update(size.into(), None);
+
+        let texture = self.timings.measure(gl, "Output", || {
+            match (&self.worker, feed) {
+                // The shader is only compiled by the worker, and its frames are stretched
+                (Some(worker), Feed::Shader | Feed::Raw) => worker.latest(gl, size, self.format),
+                _ => shader
+                    .render_to_texture(gl, &self.uniforms, size.into(), self.format)
+                    .map(Some),
+            }
+        })??;
+        let Some(texture) = texture else {
+            return Ok(None);
+        };
+
+        if feed == Feed::Raw {
+            return Ok(Some(texture));
+        }
+
+        let output = self.display.output;
+        Ok(Some(
+            self.display
+                .render(gl, *texture, size, output, false)?
+                .unwrap_or(texture),
+        ))
+    }
+
+    /// Bind the latest frame of the received NDI® source to the `ndi` sampler.
+    pub fn receive(&mut self, gl: &Rc<glow::Context>) -> Result<(), Error> {
+        let texture = match &mut self.receiver {
+            Some(receiver) => receiver.update(gl)?,
+            None => None,
+        };
+
+        self.uniforms
+            .set_texture("ndi", texture.map(|(texture, size)| (texture, size.into())));
+        self.uniforms.set_input(
+            self.receiver
+                .as_ref()
+                .and_then(Receiver::latest)
+                .map(|(size, age)| (size.into(), age)),
+        );
+
+        Ok(())
+    }
+
+    /// Send the feeds of the running outputs due for a new frame, not already shared by the canvas.
+    pub fn send(&mut self, gl: &Rc<glow::Context>) -> Result<(), Error> {
+        for index in 0..self.outputs.len() {
+            let output = &mut self.outputs[index];
+            output.poll(gl)?;
+            if !output.is_ready() {
+                continue;
+            }
+
+            let (feed, size) = (output.feed, output.resolution(self.size));
+            unsafe {
+                if let Some(texture) = self.render_feed(gl, feed, size)? {
+                    self.outputs[index].submit(gl, *texture, size)?;
                 }
             }
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,295 @@
+use eframe::egui;
+
+/// Navigates the shader's coordinate space through the `zoom`, `offset` and `rotation` uniforms,
+/// kept in double precision to allow deep zooms.
+///
+/// The shader is expected to map its fragment coordinates as follows, in `Classic` style:
+/// `u_offset + mat2(cos(u_rotation), sin(u_rotation), -sin(u_rotation), cos(u_rotation)) * (gl_FragCoord.xy - 0.5 * u_resolution) / (u_resolution.y * u_zoom)`.
+///
+/// As a `float` offset can't tell the pixels apart past a zoom of about `1e4`, it is split in two:
+/// `u_offset` holds its nearest `float`, and `u_offsetLow` the remainder. Deep zooms keep the
+/// precision by computing relative to `u_offset`, starting from `u_offsetLow` plus the mapped
+/// coordinates above, or with `dvec2(u_offset) + dvec2(u_offsetLow)` where doubles are supported.
+#[derive(Debug)]
+pub struct Navigation {
+    pub enabled: bool,
+    /// Keep moving after the canvas is released, slowing down over time.
+    pub inertia: bool,
+
+    pub zoom: f64,
+    pub offset: [f64; 2],
+    pub rotation: f64,
+
+    /// The panning speed, in shader units per second.
+    velocity: [f64; 2],
+    /// The zooming speed, in natural logarithm of the zoom per second.
+    zooming: f64,
+    /// The rotation speed, in radians per second.
+    rotating: f64,
+    /// The point zoomed and rotated around, in canvas pixel coordinates.
+    anchor: egui::Pos2,
+}
+
+impl Default for Navigation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            inertia: true,
+            zoom: 1.0,
+            offset: [0.0; 2],
+            rotation: 0.0,
+            velocity: [0.0; 2],
+            zooming: 0.0,
+            rotating: 0.0,
+            anchor: egui::Pos2::ZERO,
+        }
+    }
+}
+
+impl Navigation {
+    /// The rate at which the movements slow down after release, per second.
+    const FRICTION: f64 = 6.0;
+    /// The minimum and maximum zoom, the latter keeping the neighbouring pixels of a canvas
+    /// a thousand pixels high apart with the 48 bits of the split offset around the unit square.
+    pub const ZOOM: (f64, f64) = (1e-6, 1e11);
+
+    /// Reset to the origin, keeping the settings.
+    pub fn reset(&mut self) {
+        *self = Self {
+            enabled: self.enabled,
+            inertia: self.inertia,
+            ..Default::default()
+        };
+    }
+
+    /// Pan by dragging, zoom with the scroll wheel or a pinch, and rotate by right-dragging or twisting,
+    /// with `hovered` the hovered position in the canvas of size `resolution`, displayed magnified `scale` times.
+    pub fn interact(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        hovered: Option<egui::Pos2>,
+        resolution: egui::Vec2,
+        scale: f32,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        if response.double_clicked() {
+            self.reset();
+        }
+
+        let (dt, scroll, zoom, touch) = ui.input(|input| {
+            (
+                (input.stable_dt as f64).clamp(1e-3, 0.1),
+                input.scroll_delta.y,
+                input.zoom_delta(),
+                input.multi_touch(),
+            )
+        });
+        // Drag deltas in canvas pixels, with the y axis upwards
+        let delta = response.drag_delta() * ui.ctx().pixels_per_point() / scale;
+        let delta = [delta.x as f64, -delta.y as f64];
+
+        if response.dragged_by(egui::PointerButton::Primary) && touch.is_none() {
+            let [x, y] = self.direction(delta, resolution);
+
+            self.offset = [self.offset[0] - x, self.offset[1] - y];
+            self.velocity = [
+                (self.velocity[0] + -x / dt) / 2.0,
+                (self.velocity[1] + -y / dt) / 2.0,
+            ];
+        } else if response.dragged_by(egui::PointerButton::Secondary) {
+            let angle = delta[0] / resolution.y as f64 * std::f64::consts::TAU;
+
+            self.anchor = (resolution / 2.0).to_pos2();
+            self.rotate(angle, resolution);
+            self.rotating = (self.rotating + angle / dt) / 2.0;
+        } else if response.dragged() {
+            // Holding a touch still cancels the momentum
+            self.velocity = [0.0; 2];
+            self.rotating = 0.0;
+        }
+
+        if let Some(hovered) = hovered {
+            let factor = (zoom as f64) * (scroll as f64 / 200.0).exp();
+
+            if factor != 1.0 {
+                self.anchor = hovered;
+
+                if self.inertia {
+                    // Spread the zoom over time, reaching the same total
+                    self.zooming += factor.ln() * Self::FRICTION;
+                } else {
+                    self.scale(factor, resolution);
+                }
+            }
+
+            if let Some(touch) = touch.filter(|touch| touch.rotation_delta != 0.0) {
+                self.anchor = hovered;
+                self.rotate(touch.rotation_delta as f64, resolution);
+                self.rotating = (self.rotating + touch.rotation_delta as f64 / dt) / 2.0;
+            }
+        }
+
+        if !self.inertia {
+            self.velocity = [0.0; 2];
+            self.zooming = 0.0;
+            self.rotating = 0.0;
+        } else if !response.dragged() {
+            self.offset = [
+                self.offset[0] + self.velocity[0] * dt,
+                self.offset[1] + self.velocity[1] * dt,
+            ];
+            self.rotate(self.rotating * dt, resolution);
+        }
+        self.scale((self.zooming * dt).exp(), resolution);
+
+        let decay = (-Self::FRICTION * dt).exp();
+        self.velocity = [self.velocity[0] * decay, self.velocity[1] * decay];
+        self.zooming *= decay;
+        self.rotating *= decay;
+
+        if self.velocity[0].hypot(self.velocity[1]) * self.zoom < 1e-4 {
+            self.velocity = [0.0; 2];
+        }
+        if self.zooming.abs() < 1e-4 {
+            self.zooming = 0.0;
+        }
+        if self.rotating.abs() < 1e-4 {
+            self.rotating = 0.0;
+        }
+    }
+
+    /// The shader-space vector of the canvas-space `vector`.
+    fn direction(&self, [x, y]: [f64; 2], resolution: egui::Vec2) -> [f64; 2] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let scale = resolution.y as f64 * self.zoom;
+
+        [(cos * x - sin * y) / scale, (sin * x + cos * y) / scale]
+    }
+
+    /// The shader-space position of the canvas-space `pos`.
+    fn position(&self, pos: egui::Pos2, resolution: egui::Vec2) -> [f64; 2] {
+        let [x, y] = self.direction(
+            [
+                (pos.x - resolution.x / 2.0) as f64,
+                (pos.y - resolution.y / 2.0) as f64,
+            ],
+            resolution,
+        );
+
+        [self.offset[0] + x, self.offset[1] + y]
+    }
+
+    /// Run `transform`, keeping the shader-space position of the anchor in place.
+    fn around(&mut self, resolution: egui::Vec2, transform: impl FnOnce(&mut Self)) {
+        let before = self.position(self.anchor, resolution);
+        transform(self);
+        let after = self.position(self.anchor, resolution);
+
+        self.offset = [
+            self.offset[0] + before[0] - after[0],
+            self.offset[1] + before[1] - after[1],
+        ];
+    }
+
+    fn scale(&mut self, factor: f64, resolution: egui::Vec2) {
+        self.around(resolution, |navigation| {
+            navigation.zoom = (navigation.zoom * factor).clamp(Self::ZOOM.0, Self::ZOOM.1);
+        });
+    }
+
+    fn rotate(&mut self, angle: f64, resolution: egui::Vec2) {
+        self.around(resolution, |navigation| {
+            navigation.rotation = (navigation.rotation + angle).rem_euclid(std::f64::consts::TAU);
+        });
+    }
+
+    /// The `zoom`, `offset`, `offsetLow` and `rotation` uniform values, if enabled,
+    /// the offset being split into its nearest `float` and the remainder.
+    pub fn uniforms(&self) -> Option<(f32, [f32; 2], [f32; 2], f32)> {
+        let high = self.offset.map(|offset| offset as f32);
+        let low = [0, 1].map(|i| (self.offset[i] - high[i] as f64) as f32);
+
+        self.enabled
+            .then_some((self.zoom as f32, high, low, self.rotation as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOLUTION: egui::Vec2 = egui::vec2(800.0, 600.0);
+
+    #[test]
+    fn scale_around_anchor() {
+        let mut navigation = Navigation {
+            offset: [0.25, -0.5],
+            rotation: 1.0,
+            anchor: egui::pos2(100.0, 450.0),
+            ..Default::default()
+        };
+        let anchored = navigation.position(navigation.anchor, RESOLUTION);
+
+        navigation.scale(8.0, RESOLUTION);
+        assert_eq!(navigation.zoom, 8.0);
+
+        let position = navigation.position(navigation.anchor, RESOLUTION);
+        assert!((position[0] - anchored[0]).abs() < 1e-12);
+        assert!((position[1] - anchored[1]).abs() < 1e-12);
+
+        // The center moved towards the anchor
+        let center = navigation.position((RESOLUTION / 2.0).to_pos2(), RESOLUTION);
+        assert!((center[0] - anchored[0]).abs() < (0.25 - anchored[0]).abs());
+    }
+
+    #[test]
+    fn scale_clamped() {
+        let mut navigation = Navigation::default();
+
+        navigation.scale(1e30, RESOLUTION);
+        assert_eq!(navigation.zoom, Navigation::ZOOM.1);
+
+        navigation.scale(1e-60, RESOLUTION);
+        assert_eq!(navigation.zoom, Navigation::ZOOM.0);
+    }
+
+    #[test]
+    fn direction() {
+        let navigation = Navigation {
+            zoom: 2.0,
+            rotation: std::f64::consts::FRAC_PI_2,
+            ..Default::default()
+        };
+
+        // A quarter turn, at a scale of the canvas height times the zoom
+        let [x, y] = navigation.direction([1200.0, 0.0], RESOLUTION);
+        assert!(x.abs() < 1e-12);
+        assert!((y - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn split_offset() {
+        let mut navigation = Navigation {
+            offset: [-0.743_643_887_037_158_7, 0.131_825_904_205_311_97],
+            zoom: 1e10,
+            ..Default::default()
+        };
+        assert!(navigation.uniforms().is_none());
+
+        navigation.enabled = true;
+        let (zoom, high, low, _) = navigation.uniforms().unwrap();
+        assert_eq!(zoom, 1e10);
+
+        for i in 0..2 {
+            assert_eq!(high[i], navigation.offset[i] as f32);
+
+            // The remainder restores the offset to a hundredth of a pixel at the zoom
+            let offset = high[i] as f64 + low[i] as f64;
+            assert!((offset - navigation.offset[i]).abs() < 1e-2 / (RESOLUTION.y as f64 * 1e10));
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{self, AtomicBool, AtomicU8},
+        mpsc, Arc, Mutex,
+    },
+    thread, time,
+};
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{PixelFormat, Scan};
+use crate::{guard, AllocGuard, Error};
+
+/// A frame of RGBA rows from the bottom, as in OpenGL textures.
+struct Frame {
+    pixels: Vec<u8>,
+    size: egui::Vec2,
+}
+
+/// A frame converted to its pixel `format` on the GPU, with rows from the top.
+struct Outgoing {
+    pixels: Vec<u8>,
+    size: egui::Vec2,
+    format: PixelFormat,
+    scan: Scan,
+}
+
+/// A Newtek NDI® source, sending the latest rendered frame from a dedicated thread,
+/// paced at the configured frame rate independently of the display.
+pub struct Sender {
+    framerate: Arc<AtomicU8>,
+    latest: Arc<Mutex<Option<Outgoing>>>,
+    running: Arc<AtomicBool>,
+    errors: mpsc::Receiver<Error>,
+    /// The pixel buffers of the frames no longer sent, for reuse.
+    recycler: mpsc::Sender<Vec<u8>>,
+    recycled: mpsc::Receiver<Vec<u8>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for Sender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender")
+            .field("framerate", &self.framerate)
+            .field("running", &self.running)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Sender {
+    /// Announce the source `name` on the network and start sending at `framerate`.
+    pub fn new(name: &str, framerate: u8) -> Result<Self, Error> {
+        let send = nndi::send::Send::new(name, None).map_err(|err| Error::Ndi(err.to_string()))?;
+
+        let framerate = Arc::new(AtomicU8::new(framerate));
+        let latest = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+        let (sender, errors) = mpsc::channel();
+        let (recycler, recycled) = mpsc::channel();
+
+        let thread = thread::Builder::new().name("ndi-send".into()).spawn({
+            let (framerate, latest, running) = (framerate.clone(), latest.clone(), running.clone());
+            let recycler = recycler.clone();
+
+            move || Self::run(send, framerate, latest, running, sender, recycler)
+        })?;
+
+        tracing::info!("Started sending NDI® source `{name}`");
+
+        Ok(Self {
+            framerate,
+            latest,
+            running,
+            errors,
+            recycler,
+            recycled,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn set_framerate(&self, framerate: u8) {
+        self.framerate.store(framerate, atomic::Ordering::Relaxed);
+    }
+
+    /// Submit `pixels`, the rows of a frame of `size` from the top packed in the `format`, for sending.
+    pub fn submit(&self, pixels: Vec<u8>, size: egui::Vec2, format: PixelFormat, scan: Scan) {
+        if size.x < 1.0 || size.y < 1.0 {
+            return;
+        }
+
+        let previous = self
+            .latest
+            .lock()
+            .expect("NDI® sending thread panicked")
+            .replace(Outgoing {
+                pixels,
+                size,
+                format,
+                scan,
+            });
+
+        // The frame was replaced before it could be sent
+        if let Some(previous) = previous {
+            let _ = self.recycler.send(previous.pixels);
+        }
+    }
+
+    /// A pixel buffer of a frame no longer sent, to be reused for the next ones.
+    pub fn reclaim(&self) -> Option<Vec<u8>> {
+        self.recycled.try_recv().ok()
+    }
+
+    /// Report the errors that occured while sending.
+    pub fn poll(&self) -> Result<(), Error> {
+        match self.errors.try_recv() {
+            Ok(err) => Err(err),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Send the latest frame at each tick of the frame rate, repeating it if no new one was submitted.
+    fn run(
+        send: nndi::send::Send,
+        framerate: Arc<AtomicU8>,
+        latest: Arc<Mutex<Option<Outgoing>>>,
+        running: Arc<AtomicBool>,
+        errors: mpsc::Sender<Error>,
+        recycler: mpsc::Sender<Vec<u8>>,
+    ) {
+        let epoch = time::SystemTime::now()
+            .duration_since(time::UNIX_EPOCH)
+            .expect("Time went backwards >.>");
+        let started = time::Instant::now();
+
+        let mut next = started;
+        let mut video = None;
+
+        while running.load(atomic::Ordering::Relaxed) {
+            let framerate = framerate.load(atomic::Ordering::Relaxed).max(1);
+
+            let frame = latest.lock().expect("Renderer panicked").take();
+            if let Some(previous) = frame.and_then(|frame| video.replace(Self::video(frame))) {
+                let _ = recycler.send(previous.data);
+            }
+
+            if let Some(video) = &mut video {
+                video.frame_rate = (framerate.into(), 1);
+                // The timecode of the tick since the UNIX epoch, in 100ns units
+                video.timecode = ((epoch + (next - started)).as_nanos() / 100) as i64;
+
+                if let Err(err) = send.send_video(video) {
+                    let _ = errors.send(Error::Ndi(err.to_string()));
+                }
+            }
+
+            next += time::Duration::from_secs_f64(1.0 / framerate as f64);
+            match next.checked_duration_since(time::Instant::now()) {
+                Some(wait) => thread::sleep(wait),
+                // Skip the missed ticks rather than catching up
+                None => next = time::Instant::now(),
+            }
+        }
+    }
+
+    /// Wrap the `frame` in a video frame, the pixels being already laid out as NDI® expects them,
+    /// with the UYVA alpha plane following the UYVY one.
+    fn video(frame: Outgoing) -> nndi::video::Frame {
+        let width = frame.size.x as usize;
+        let (fourcc, stride) = match frame.format {
+            PixelFormat::Rgba => (nndi::video::FourCC::RGBA, width * 4),
+            PixelFormat::Uyvy => (nndi::video::FourCC::UYVY, width * 2),
+            PixelFormat::Uyva => (nndi::video::FourCC::UYVA, width * 2),
+        };
+        let frame_format = match frame.scan {
+            Scan::Progressive => nndi::video::FrameFormat::Progressive,
+            Scan::Interlaced => nndi::video::FrameFormat::Interleaved,
+        };
+
+        nndi::video::Frame {
+            width: frame.size.x as u32,
+            height: frame.size.y as u32,
+            fourcc,
+            frame_rate: (30, 1),
+            frame_format,
+            timecode: 0,
+            stride,
+            data: frame.pixels,
+        }
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        self.running.store(false, atomic::Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Discovers the NDI® sources announced on the network, including the local ones.
+pub struct Discovery(nndi::scan::Scan);
+
+impl std::fmt::Debug for Discovery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Discovery").finish_non_exhaustive()
+    }
+}
+
+impl Discovery {
+    pub fn new() -> Result<Self, Error> {
+        nndi::scan::Scan::new()
+            .map(Self)
+            .map_err(|err| Error::Ndi(err.to_string()))
+    }
+
+    /// The sources discovered so far.
+    pub fn sources(&self) -> Vec<nndi::Source> {
+        self.0.sources()
+    }
+}
+
+/// A Newtek NDI® source received from a dedicated thread,
+/// its latest frame uploaded to a texture for the shader.
+pub struct Receiver {
+    source: nndi::Source,
+    latest: Arc<Mutex<Option<(Frame, time::Instant)>>>,
+    running: Arc<AtomicBool>,
+    errors: mpsc::Receiver<Error>,
+    thread: Option<thread::JoinHandle<()>>,
+
+    texture: Option<AllocGuard<glow::Texture>>,
+    size: egui::Vec2,
+    received: Option<time::Instant>,
+}
+
+impl std::fmt::Debug for Receiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver")
+            .field("source", &self.source.name())
+            .field("size", &self.size)
+            .field("received", &self.received)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Receiver {
+    /// The time waited for a frame before checking whether to stop receiving.
+    const TIMEOUT: time::Duration = time::Duration::from_millis(100);
+
+    /// Connect to the `source` and start receiving its frames.
+    pub fn new(source: nndi::Source) -> Result<Self, Error> {
+        let recv = nndi::recv::Recv::new(&source).map_err(|err| Error::Ndi(err.to_string()))?;
+
+        let latest = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+        let (sender, errors) = mpsc::channel();
+
+        let thread = thread::Builder::new().name("ndi-recv".into()).spawn({
+            let (latest, running) = (latest.clone(), running.clone());
+
+            move || Self::run(recv, latest, running, sender)
+        })?;
+
+        tracing::info!("Started receiving NDI® source `{}`", source.name());
+
+        Ok(Self {
+            source,
+            latest,
+            running,
+            errors,
+            thread: Some(thread),
+            texture: None,
+            size: egui::Vec2::ZERO,
+            received: None,
+        })
+    }
+
+    pub fn source(&self) -> &nndi::Source {
+        &self.source
+    }
+
+    /// The size of the latest frame, and the time elapsed since it was received, in seconds.
+    pub fn latest(&self) -> Option<(egui::Vec2, f32)> {
+        self.received
+            .map(|received| (self.size, received.elapsed().as_secs_f32()))
+    }
+
+    /// Upload the latest received frame, returning the texture and its size if any frame was received yet.
+    pub fn update(
+        &mut self,
+        gl: &Rc<glow::Context>,
+    ) -> Result<Option<(glow::Texture, egui::Vec2)>, Error> {
+        if let Ok(err) = self.errors.try_recv() {
+            return Err(err);
+        }
+
+        let latest = self
+            .latest
+            .lock()
+            .expect("NDI® receiving thread panicked")
+            .take();
+        if let Some((frame, received)) = latest {
+            unsafe {
+                let texture = match &self.texture {
+                    Some(texture) => **texture,
+                    None => **self.texture.insert(guard!(
+                        gl,
+                        gl.create_texture().map_err(Error::Gl)?,
+                        move |texture| gl.delete_texture(texture)
+                    )),
+                };
+
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    glow::RGBA8 as i32,
+                    frame.size.x as i32,
+                    frame.size.y as i32,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    Some(&frame.pixels),
+                );
+                gl.tex_parameter_i32(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_MIN_FILTER,
+                    glow::LINEAR as i32,
+                );
+                gl.tex_parameter_i32(
+                    glow::TEXTURE_2D,
+                    glow::TEXTURE_MAG_FILTER,
+                    glow::LINEAR as i32,
+                );
+                gl.bind_texture(glow::TEXTURE_2D, None);
+            }
+
+            self.size = frame.size;
+            self.received = Some(received);
+        }
+
+        Ok(self.texture.as_ref().map(|texture| (**texture, self.size)))
+    }
+
+    /// Receive the frames, converting them to RGBA rows from the bottom and keeping only the latest.
+    fn run(
+        recv: nndi::recv::Recv,
+        latest: Arc<Mutex<Option<(Frame, time::Instant)>>>,
+        running: Arc<AtomicBool>,
+        errors: mpsc::Sender<Error>,
+    ) {
+        while running.load(atomic::Ordering::Relaxed) {
+            let frame = recv
+                .video(Self::TIMEOUT)
+                .map_err(|err| Error::Ndi(err.to_string()))
+                .and_then(|video| video.as_ref().map(Self::convert).transpose());
+
+            match frame {
+                Ok(Some(frame)) => {
+                    *latest.lock().expect("Renderer panicked") = Some((frame, time::Instant::now()))
+                }
+                Ok(None) => (),
+                Err(err) => {
+                    if errors.send(err).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Convert the received `video` frame to RGBA rows from the bottom, as expected by OpenGL.
+    fn convert(video: &nndi::video::Frame) -> Result<Frame, Error> {
+        use nndi::video::FourCC;
+
+        let (width, height, stride) = (video.width as usize, video.height as usize, video.stride);
+        let line = match video.fourcc {
+            FourCC::UYVY | FourCC::UYVA => width * 2,
+            FourCC::RGBA | FourCC::RGBX | FourCC::BGRA | FourCC::BGRX => width * 4,
+        };
+        // The UYVA alpha plane follows the UYVY one, a byte per pixel
+        let (plane, alpha) = video.data.split_at(video.data.len().min(stride * height));
+
+        if stride < line
+            || plane.len() < stride * height
+            || (video.fourcc == FourCC::UYVA && alpha.len() < width * height)
+        {
+            return Err(Error::Ndi(format!(
+                "Received a truncated {width}x{height} {:?} frame",
+                video.fourcc
+            )));
+        }
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for row in (0..height).rev() {
+            let texels = &plane[row * stride..][..line];
+
+            match video.fourcc {
+                FourCC::RGBA => pixels.extend_from_slice(texels),
+                FourCC::RGBX => pixels.extend(
+                    texels
+                        .chunks_exact(4)
+                        .flat_map(|texel| [texel[0], texel[1], texel[2], u8::MAX]),
+                ),
+                FourCC::BGRA | FourCC::BGRX => {
+                    let opaque = video.fourcc == FourCC::BGRX;
+
+                    pixels.extend(texels.chunks_exact(4).flat_map(|texel| {
+                        [
+                            texel[2],
+                            texel[1],
+                            texel[0],
+                            if opaque { u8::MAX } else { texel[3] },
+                        ]
+                    }))
+                }
+                FourCC::UYVY | FourCC::UYVA => {
+                    let alpha =
+                        (video.fourcc == FourCC::UYVA).then(|| &alpha[row * width..][..width]);
+
+                    for (index, texel) in texels.chunks_exact(4).enumerate() {
+                        let [u, y0, v, y1] = [texel[0], texel[1], texel[2], texel[3]];
+
+                        for (offset, y) in [y0, y1].into_iter().enumerate() {
+                            let [r, g, b] = Self::rgb(y, u, v);
+                            let a = alpha.map_or(u8::MAX, |alpha| alpha[index * 2 + offset]);
+
+                            pixels.extend([r, g, b, a]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Frame {
+            pixels,
+            size: egui::vec2(width as f32, height as f32),
+        })
+    }
+
+    /// The RGB color of the BT.709 luma and chroma, in limited range, as sent by the outputs.
+    fn rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+        let y = (y as f32 - 16.0) / 219.0;
+        let (u, v) = ((u as f32 - 128.0) / 224.0, (v as f32 - 128.0) / 224.0);
+
+        [y + 1.5748 * v, y - 0.1873 * u - 0.4681 * v, y + 1.8556 * u]
+            .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        self.running.store(false, atomic::Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use std::{rc::Rc, time};
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+use strum::{AsRefStr, EnumIter};
+
+use super::{pass::Pass, readback::Readback, Format, Sender};
+use crate::Error;
+
+/// The pass converting the feed to the pixel format of the output, with the origin on the top-left.
+///
+/// The UYVY pixels are packed by pairs in the RGBA texels, and for UYVA
+/// the alpha plane follows in the lower half-height, four values per texel.
+const CONVERT: &str = r#"
+    #version 330 core
+
+    uniform sampler2D _glou_input;
+    uniform int _glou_format;
+    uniform int _glou_alpha;
+
+    out vec4 _glou_output;
+
+    ivec2 size;
+
+    vec4 fetch(int x, int y) {
+        vec4 color = texelFetch(_glou_input, ivec2(x, size.y - 1 - y), 0);
+
+        if (_glou_alpha == 0) {
+            color.a = 1.0;
+        } else if (_glou_alpha == 2) {
+            color.rgb *= color.a;
+        }
+
+        return clamp(color, 0.0, 1.0);
+    }
+
+    // The BT.709 luma and chroma, in limited range
+    float luma(vec3 color) {
+        return (16.0 + 219.0 * dot(color, vec3(0.2126, 0.7152, 0.0722))) / 255.0;
+    }
+    vec2 chroma(vec3 color) {
+        return (128.0 + 224.0 * vec2(
+            dot(color, vec3(-0.1146, -0.3854, 0.5)),
+            dot(color, vec3(0.5, -0.4542, -0.0458))
+        )) / 255.0;
+    }
+
+    void main() {
+        ivec2 coord = ivec2(gl_FragCoord.xy);
+        size = textureSize(_glou_input, 0);
+
+        if (_glou_format == 0) {
+            _glou_output = fetch(coord.x, coord.y);
+        } else if (coord.y < size.y) {
+            vec4 left = fetch(coord.x * 2, coord.y), right = fetch(coord.x * 2 + 1, coord.y);
+            vec2 uv = chroma((left.rgb + right.rgb) / 2.0);
+
+            _glou_output = vec4(uv.x, luma(left.rgb), uv.y, luma(right.rgb));
+        } else {
+            int first = (coord.y - size.y) * size.x * 2 + coord.x * 4;
+
+            for (int i = 0; i < 4; i++) {
+                _glou_output[i] = fetch((first + i) % size.x, (first + i) / size.x).a;
+            }
+        }
+    }
+"#;
+
+/// The pixel format of the frames sent by an [`Output`].
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+pub enum PixelFormat {
+    /// 8-bit RGBA, with alpha.
+    #[default]
+    #[strum(serialize = "RGBA")]
+    Rgba,
+    /// 8-bit 4:2:2 YUV, converted on the GPU.
+    #[strum(serialize = "UYVY")]
+    Uyvy,
+    /// 8-bit 4:2:2 YUV with a full-resolution alpha plane, converted on the GPU.
+    #[strum(serialize = "UYVA")]
+    Uyva,
+}
+
+/// The meaning of the alpha channel of the frames sent by an [`Output`].
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+pub enum Alpha {
+    /// Discard the alpha of the feed, sending opaque frames.
+    #[default]
+    Opaque,
+    /// Send the alpha of the feed as-is, the colors not multiplied by it.
+    Straight,
+    /// Multiply the colors by the alpha of the feed.
+    Premultiplied,
+}
+
+/// Whether the frames sent by an [`Output`] are advertised as progressive or interlaced.
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+pub enum Scan {
+    /// Progressive, whole frames.
+    #[default]
+    Progressive,
+    /// Interlaced, with the top field first.
+    Interlaced,
+}
+
+/// The render sent by an [`Output`].
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+pub enum Feed {
+    /// The render of the shader, mapped to the output encoding.
+    #[default]
+    Shader,
+    /// The render of the shader, without any color mapping.
+    Raw,
+    /// The render of the comparison shader, mapped to the output encoding.
+    Comparison,
+}
+
+/// A Newtek NDI® output, sending a feed at its own resolution and frame rate.
+#[derive(Debug)]
+pub struct Output {
+    pub name: String,
+    /// The resolution of the feed, or the render texture size if `None`.
+    pub size: Option<egui::Vec2>,
+    pub framerate: u8,
+    pub feed: Feed,
+    pub format: PixelFormat,
+    pub alpha: Alpha,
+    pub scan: Scan,
+
+    sender: Option<Sender>,
+    pass: Option<Pass>,
+    readback: Readback<(egui::Vec2, PixelFormat, Scan)>,
+    buffer: Vec<u8>,
+    /// The time the next frame is due, at the frame rate.
+    due: Option<time::Instant>,
+}
+
+impl Output {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            size: None,
+            framerate: 30,
+            feed: Default::default(),
+            format: Default::default(),
+            alpha: Default::default(),
+            scan: Default::default(),
+            sender: None,
+            pass: None,
+            readback: Default::default(),
+            buffer: Vec::new(),
+            due: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    pub fn start(&mut self) -> Result<(), Error> {
+        self.sender = Some(Sender::new(&self.name, self.framerate)?);
+
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        self.sender = None;
+        self.readback = Default::default();
+        self.due = None;
+    }
+
+    /// Whether the output is running and due for a new frame, with a free readback buffer.
+    pub(super) fn is_ready(&self) -> bool {
+        self.sender.is_some()
+            && !self.readback.is_full()
+            && self.due.map_or(true, |due| time::Instant::now() >= due)
+    }
+
+    /// Report the error that occured while sending, stopping the output, and submit the frames read back since.
+    pub(super) fn poll(&mut self, gl: &Rc<glow::Context>) -> Result<(), Error> {
+        let Some(sender) = &self.sender else {
+            return Ok(());
+        };
+
+        sender.set_framerate(self.framerate);
+        // The sending errors tend to persist, and would be reported again at each frame
+        if let Err(err) = sender.poll() {
+            self.stop();
+
+            return Err(err);
+        }
+
+        // Read back into the buffers of the frames already sent rather than allocating new ones
+        if self.buffer.capacity() == 0 {
+            self.buffer = sender.reclaim().unwrap_or_default();
+        }
+        if let Some((size, format, scan)) = unsafe { self.readback.collect(gl, &mut self.buffer) } {
+            sender.submit(std::mem::take(&mut self.buffer), size, format, scan);
+        }
+
+        Ok(())
+    }
+
+    /// The resolution of the feed given the render texture `size`, made even for the 4:2:2 formats.
+    pub(super) fn resolution(&self, size: egui::Vec2) -> egui::Vec2 {
+        let size = self.size.unwrap_or(size);
+
+        match self.format {
+            PixelFormat::Rgba => size,
+            PixelFormat::Uyvy | PixelFormat::Uyva => (size / 2.0).floor() * 2.0,
+        }
+    }
+
+    /// Convert the `texture` of `size` to the pixel format and start reading it back for sending.
+    pub(super) unsafe fn submit(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        size: egui::Vec2,
+    ) -> Result<(), Error> {
+        if self.sender.is_none() {
+            return Ok(());
+        }
+
+        // The size of the texture holding the converted pixels
+        let packed = match self.format {
+            PixelFormat::Rgba => size,
+            PixelFormat::Uyvy => egui::vec2(size.x / 2.0, size.y),
+            PixelFormat::Uyva => egui::vec2(size.x / 2.0, size.y * 1.5),
+        };
+
+        let pass = match &self.pass {
+            Some(pass) => pass,
+            None => self.pass.insert(Pass::new(gl, CONVERT)?),
+        };
+        let converted = pass.render_to_texture(
+            gl,
+            packed,
+            Format::Rgba8,
+            &[("_glou_input", texture)],
+            |program| {
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_format").as_ref(),
+                    self.format as i32,
+                );
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "_glou_alpha").as_ref(),
+                    self.alpha as i32,
+                );
+            },
+        )?;
+
+        if self.readback.read(
+            gl,
+            *converted,
+            packed,
+            Format::Rgba8,
+            (size, self.format, self.scan),
+        )? {
+            // Keep to the frame rate, unless more than a frame late
+            let now = time::Instant::now();
+            let period = time::Duration::from_secs_f64(1.0 / self.framerate.max(1) as f64);
+            let due = self
+                .due
+                .filter(|due| now.saturating_duration_since(*due) < period)
+                .unwrap_or(now);
+
+            self.due = Some(due + period);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{shader::Program, target, Format, Shader};
+use crate::{AllocGuard, Error};
+
+/// A fullscreen pass processing textures, such as the ones rendered by a [`Shader`].
+#[derive(Debug)]
+pub struct Pass(Program);
+
+impl Pass {
+    pub unsafe fn new(gl: &Rc<glow::Context>, source: &str) -> Result<Self, Error> {
+        Ok(Self(Shader::compile(gl, source)?))
+    }
+
+    /// Render the pass to a texture of `size` and `format`, binding the `inputs` to their samplers
+    /// and letting `setup` set the remaining uniforms of the program.
+    pub unsafe fn render_to_texture(
+        &self,
+        gl: &Rc<glow::Context>,
+        size: egui::Vec2,
+        format: Format,
+        inputs: &[(&str, glow::Texture)],
+        setup: impl FnOnce(glow::Program),
+    ) -> Result<AllocGuard<glow::Texture>, Error> {
+        let (program, vertices) = &self.0;
+
+        target::render_to_texture(gl, size.into(), format, || {
+            gl.use_program(Some(**program));
+
+            for (unit, (name, texture)) in inputs.iter().enumerate() {
+                let location = gl.get_uniform_location(**program, name);
+
+                gl.active_texture(glow::TEXTURE0 + unit as u32);
+                gl.bind_texture(glow::TEXTURE_2D, Some(*texture));
+                gl.uniform_1_i32(location.as_ref(), unit as i32);
+            }
+            gl.active_texture(glow::TEXTURE0);
+
+            setup(**program);
+
+            gl.bind_vertex_array(Some(**vertices));
+            gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+            gl.bind_vertex_array(None);
+        })
+    }
+}
@@ -0,0 +1,351 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{pass::Pass, Format};
+use crate::{
+    canvas::{Canvas, View},
+    Error,
+};
+
+/// The header of the plotting shader, followed by a function per expression.
+const HEADER: &str = r#"
+    #version 330 core
+
+    uniform vec2 _glou_resolution;
+    uniform vec2 _glou_center;
+    uniform float _glou_scale;
+    uniform float _glou_time;
+    uniform vec3 _glou_colors[8];
+
+    out vec4 _glou_output;
+
+    const float PI = 3.14159265358979;
+
+    // Blend the curve going from `y0` to `y1` over a pixel into `color`, at the height `y`
+    vec3 _glou_curve(vec3 color, float y, float y0, float y1, vec3 stroke) {
+        float slope = (y1 - y0) / _glou_scale;
+        float distance = abs(y0 - y) / _glou_scale / sqrt(1.0 + slope * slope);
+
+        if (isnan(distance) || isinf(distance)) {
+            return color;
+        }
+
+        return mix(color, stroke, 1.0 - smoothstep(0.75, 1.75, distance));
+    }
+"#;
+
+/// The body of the plotting shader, drawing the grid, the axes and the curves.
+const BODY: &str = r#"
+    void main() {
+        vec2 world = _glou_center + (gl_FragCoord.xy - _glou_resolution / 2.0) * _glou_scale;
+        float x = world.x, y = world.y, t = _glou_time;
+
+        // The grid of the powers of ten spanning about a hundred pixels, and the axes
+        float spacing = pow(10.0, floor(log(_glou_scale * 100.0) / log(10.0)));
+        vec2 grid = abs(fract(world / spacing + 0.5) - 0.5) * spacing / _glou_scale;
+        vec2 axes = abs(world) / _glou_scale;
+
+        vec3 color = vec3(0.08);
+        color = mix(color, vec3(0.18), float(min(grid.x, grid.y) < 0.5));
+        color = mix(color, vec3(0.45), float(min(axes.x, axes.y) < 1.0));
+"#;
+
+/// Plots GLSL expressions of `x` and `t`, compiled into a dedicated shader.
+#[derive(Debug)]
+pub struct Plotter {
+    /// Show the plot window.
+    pub open: bool,
+    /// The plotted expressions, with the color of their curve.
+    pub expressions: Vec<(String, egui::Color32)>,
+
+    /// The point at the center of the plot.
+    center: egui::Vec2,
+    /// The units per physical pixel.
+    scale: f32,
+
+    source: String,
+    pass: Option<Pass>,
+    /// The error of the plotting shader, kept until it compiles.
+    error: Option<Error>,
+}
+
+impl Default for Plotter {
+    fn default() -> Self {
+        Self {
+            open: false,
+            expressions: vec![("sin(x * PI + t)".to_string(), Self::PALETTE[0])],
+            center: egui::Vec2::ZERO,
+            scale: Self::SCALE,
+            source: String::new(),
+            pass: None,
+            error: None,
+        }
+    }
+}
+
+impl Plotter {
+    /// The maximum number of plotted expressions.
+    pub const MAX: usize = 8;
+
+    /// The colors given to the new expressions.
+    pub const PALETTE: [egui::Color32; Self::MAX] = [
+        egui::Color32::from_rgb(255, 196, 0),
+        egui::Color32::from_rgb(80, 160, 255),
+        egui::Color32::from_rgb(255, 90, 90),
+        egui::Color32::from_rgb(110, 220, 110),
+        egui::Color32::from_rgb(200, 120, 255),
+        egui::Color32::from_rgb(0, 220, 220),
+        egui::Color32::from_rgb(255, 150, 60),
+        egui::Color32::from_rgb(230, 230, 230),
+    ];
+
+    /// The default units per physical pixel.
+    const SCALE: f32 = 1.0 / 100.0;
+
+    /// Add an expression with the next color of the palette, up to [`Plotter::MAX`].
+    pub fn add(&mut self) {
+        if self.expressions.len() < Self::MAX {
+            let color = Self::PALETTE[self.expressions.len() % Self::MAX];
+
+            self.expressions.push((String::new(), color));
+        }
+    }
+
+    /// Recompile the plotting shader if the expressions changed, keeping its error until it compiles.
+    pub fn update(&mut self, gl: &Rc<glow::Context>) {
+        match self.rebuild(gl) {
+            Ok(true) => self.error = None,
+            Ok(false) => (),
+            Err(err) => {
+                tracing::warn!("An error occured while compiling plot shader: {err}");
+
+                self.error = Some(err);
+            }
+        }
+    }
+
+    /// Recompile the plotting shader if the expressions changed, returning whether it changed.
+    fn rebuild(&mut self, gl: &Rc<glow::Context>) -> Result<bool, Error> {
+        let expressions = || {
+            self.expressions
+                .iter()
+                .enumerate()
+                .filter(|(_, (expression, _))| !expression.trim().is_empty())
+                .map(|(index, (expression, _))| (index, expression.as_str()))
+        };
+        let source = Self::source(expressions());
+
+        // Don't recompile the same shader every frame, even if erroneous
+        if source == self.source {
+            return Ok(false);
+        }
+
+        self.source = source;
+        self.pass = None;
+
+        match unsafe { Pass::new(gl, &self.source) } {
+            Ok(pass) => {
+                self.pass = Some(pass);
+
+                Ok(true)
+            }
+            // Compile the expressions one by one to point at the first erroneous one
+            Err(err) => Err(expressions()
+                .find_map(|(index, expression)| {
+                    let source = Self::source(std::iter::once((index, expression)));
+
+                    unsafe { Pass::new(gl, &source) }.err().map(|err| {
+                        Error::Compile(format!("In plotted expression {}, {err}", index + 1))
+                    })
+                })
+                .unwrap_or(err)),
+        }
+    }
+
+    /// Build the plotting shader of the `expressions` with their index, each on its own line
+    /// numbered 1 so that the compile errors point at the expression.
+    fn source<'e>(expressions: impl Iterator<Item = (usize, &'e str)>) -> String {
+        let mut source = HEADER.to_string();
+        let mut curves = String::new();
+
+        for (index, expression) in expressions {
+            source += &format!(
+                "\n#line 1\n    float _glou_plot{index}(float x, float t) {{ return float({expression}); }}\n"
+            );
+            curves += &format!(
+                "        color = _glou_curve(color, y, _glou_plot{index}(x, t), _glou_plot{index}(x + _glou_scale, t), _glou_colors[{index}]);\n"
+            );
+        }
+
+        source += BODY;
+        source += &curves;
+        source += "\n        _glou_output = vec4(color, 1.0);\n    }\n";
+
+        source
+    }
+
+    /// Pan by dragging, zoom with the scroll wheel around the cursor and reset on double-click.
+    fn interact(&mut self, ui: &egui::Ui, response: &egui::Response, viewport: egui::Rect) {
+        let pixels_per_point = ui.ctx().pixels_per_point();
+
+        if response.double_clicked() {
+            self.center = egui::Vec2::ZERO;
+            self.scale = Self::SCALE;
+        }
+
+        if response.dragged() {
+            let delta = response.drag_delta() * pixels_per_point;
+
+            self.center -= egui::vec2(delta.x, -delta.y) * self.scale;
+        }
+
+        if let Some(pos) = response.hover_pos() {
+            let (scroll, zoom) = ui.input(|input| (input.scroll_delta.y, input.zoom_delta()));
+            let scale = (self.scale / zoom / (scroll / 200.0).exp()).clamp(1e-6, 1e4);
+
+            // Keep the point under the cursor in place
+            let anchor = View::local(viewport, pos, pixels_per_point) - viewport.size() / 2.0;
+            self.center += anchor.to_vec2() * (self.scale - scale);
+            self.scale = scale;
+        }
+    }
+
+    /// Show the plot in a window, with `time` the value of `t`.
+    pub fn show(&mut self, gl: &Rc<glow::Context>, ctx: &egui::Context, time: f32) {
+        let mut open = self.open;
+
+        egui::Window::new("📈 Plotter")
+            .open(&mut open)
+            .default_size(egui::vec2(480.0, 320.0))
+            .show(ctx, |ui| {
+                if let Some(err) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, err.to_string().trim_end());
+                }
+
+                let (response, painter) =
+                    ui.allocate_painter(ui.available_size(), egui::Sense::click_and_drag());
+                let viewport = egui::Rect {
+                    min: painter.round_pos_to_pixels(painter.clip_rect().min),
+                    max: painter.round_pos_to_pixels(painter.clip_rect().max),
+                } * painter.ctx().pixels_per_point();
+
+                self.interact(ui, &response, viewport);
+
+                let texture = self.pass.as_ref().and_then(|pass| {
+                    unsafe {
+                        pass.render_to_texture(gl, viewport.size(), Format::Rgba8, &[], |program| {
+                            gl.uniform_2_f32(
+                                gl.get_uniform_location(program, "_glou_resolution")
+                                    .as_ref(),
+                                viewport.width(),
+                                viewport.height(),
+                            );
+                            gl.uniform_2_f32(
+                                gl.get_uniform_location(program, "_glou_center").as_ref(),
+                                self.center.x,
+                                self.center.y,
+                            );
+                            gl.uniform_1_f32(
+                                gl.get_uniform_location(program, "_glou_scale").as_ref(),
+                                self.scale,
+                            );
+                            gl.uniform_1_f32(
+                                gl.get_uniform_location(program, "_glou_time").as_ref(),
+                                time,
+                            );
+
+                            for (index, (_, color)) in self.expressions.iter().enumerate() {
+                                let [r, g, b, _] = color.to_normalized_gamma_f32();
+
+                                gl.uniform_3_f32(
+                                    gl.get_uniform_location(
+                                        program,
+                                        &format!("_glou_colors[{index}]"),
+                                    )
+                                    .as_ref(),
+                                    r,
+                                    g,
+                                    b,
+                                );
+                            }
+                        })
+                    }
+                    .map_err(|err| tracing::warn!("Unable to render plot: {err}"))
+                    .ok()
+                });
+
+                let labels = painter.clone();
+                Canvas::new(texture, painter, response, View::default()).paint();
+
+                self.label(&labels, viewport);
+            });
+
+        self.open = open;
+    }
+
+    /// Label the grid lines along the axes, kept within the plot.
+    fn label(&self, painter: &egui::Painter, viewport: egui::Rect) {
+        let pixels_per_point = painter.ctx().pixels_per_point();
+        let rect = painter.clip_rect();
+        let spacing = 10f32.powf((self.scale * 100.0).log10().floor());
+
+        // From plot coordinates to screen points
+        let screen = |x: f32, y: f32| {
+            let pixel = (egui::vec2(x, y) - self.center) / self.scale;
+
+            rect.center() + egui::vec2(pixel.x, -pixel.y) / pixels_per_point
+        };
+        let (min, max) = (
+            self.center - viewport.size() / 2.0 * self.scale,
+            self.center + viewport.size() / 2.0 * self.scale,
+        );
+
+        let font = egui::FontId::monospace(10.0);
+        let color = egui::Color32::from_gray(160);
+        let format = |value: f32| {
+            let value = (value / spacing).round() * spacing;
+
+            match spacing < 1.0 {
+                true => format!("{:.*}", (-spacing.log10()).ceil() as usize, value),
+                false => format!("{value:.0}"),
+            }
+        };
+
+        let (x, y) = (
+            screen(0.0, 0.0).y.clamp(rect.top(), rect.bottom() - 12.0),
+            screen(0.0, 0.0)
+                .x
+                .clamp(rect.left() + 2.0, rect.right() - 32.0),
+        );
+
+        let mut value = (min.x / spacing).ceil() * spacing;
+        while value <= max.x {
+            painter.text(
+                egui::pos2(screen(value, 0.0).x + 2.0, x),
+                egui::Align2::LEFT_TOP,
+                format(value),
+                font.clone(),
+                color,
+            );
+            value += spacing;
+        }
+
+        let mut value = (min.y / spacing).ceil() * spacing;
+        while value <= max.y {
+            if (value / spacing).round() != 0.0 {
+                painter.text(
+                    egui::pos2(y + 2.0, screen(0.0, value).y),
+                    egui::Align2::LEFT_BOTTOM,
+                    format(value),
+                    font.clone(),
+                    color,
+                );
+            }
+            value += spacing;
+        }
+    }
+}
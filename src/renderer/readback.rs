@@ -0,0 +1,183 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{Format, Pool};
+use crate::{guard, AllocGuard, Error};
+
+/// A pixel buffer object of the ring, with the fence of its pending read.
+struct Slot<T> {
+    buffer: AllocGuard<glow::Buffer>,
+    capacity: usize,
+    pending: Option<(AllocGuard<glow::Fence>, usize, T)>,
+}
+
+/// A ring of pixel buffer objects reading textures back asynchronously,
+/// their RGBA pixels collected once the GPU is done with them, a frame or two later.
+pub(super) struct Readback<T> {
+    slots: Vec<Slot<T>>,
+    next: usize,
+    /// The framebuffer reading the textures which aren't render targets of the [`Pool`].
+    framebuffer: Option<AllocGuard<glow::Framebuffer>>,
+}
+
+impl<T> std::fmt::Debug for Readback<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Readback")
+            .field("slots", &self.slots.len())
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Default for Readback<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            next: 0,
+            framebuffer: None,
+        }
+    }
+}
+
+impl<T> Readback<T> {
+    /// The number of reads in flight at most.
+    const LEN: usize = 3;
+
+    /// Whether all the buffers of the ring are waiting for their read to complete.
+    pub fn is_full(&self) -> bool {
+        self.slots
+            .get(self.next)
+            .is_some_and(|slot| slot.pending.is_some())
+    }
+
+    /// Start reading back the RGBA pixels of the `texture` of `size` in the type of `format`, tagged with `tag`,
+    /// returning `false` if the ring was full.
+    pub unsafe fn read(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        size: egui::Vec2,
+        format: Format,
+        tag: T,
+    ) -> Result<bool, Error> {
+        let region = egui::Rect::from_min_size(egui::Pos2::ZERO, size);
+
+        self.read_region(gl, texture, region, format, tag)
+    }
+
+    /// Start reading back the RGBA pixels of the `region` of the `texture`, in pixels, as [`Readback::read`] does.
+    pub unsafe fn read_region(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        region: egui::Rect,
+        format: Format,
+        tag: T,
+    ) -> Result<bool, Error> {
+        if self.is_full() {
+            return Ok(false);
+        }
+
+        if self.slots.len() <= self.next {
+            let buffer = gl.create_buffer().map_err(Error::Gl)?;
+
+            self.slots.push(Slot {
+                buffer: guard!(gl, buffer, move |buffer| gl.delete_buffer(buffer)),
+                capacity: 0,
+                pending: None,
+            });
+        }
+        let slot = &mut self.slots[self.next];
+        let (width, height) = (region.width() as i32, region.height() as i32);
+        let len = width as usize * height as usize * format.bytes();
+
+        gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(*slot.buffer));
+        if slot.capacity < len {
+            gl.buffer_data_size(glow::PIXEL_PACK_BUFFER, len as i32, glow::STREAM_READ);
+            slot.capacity = len;
+        }
+
+        match Pool::framebuffer(texture) {
+            Some(framebuffer) => gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(framebuffer)),
+            None => {
+                let framebuffer = match &self.framebuffer {
+                    Some(framebuffer) => framebuffer,
+                    None => self.framebuffer.insert(guard!(
+                        gl,
+                        gl.create_framebuffer().map_err(Error::Gl)?,
+                        move |framebuffer| gl.delete_framebuffer(framebuffer)
+                    )),
+                };
+
+                gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(**framebuffer));
+                gl.framebuffer_texture_2d(
+                    glow::READ_FRAMEBUFFER,
+                    glow::COLOR_ATTACHMENT0,
+                    glow::TEXTURE_2D,
+                    Some(texture),
+                    0,
+                );
+            }
+        }
+        gl.read_buffer(glow::COLOR_ATTACHMENT0);
+        gl.read_pixels(
+            region.min.x as i32,
+            region.min.y as i32,
+            width,
+            height,
+            glow::RGBA,
+            format.ty(),
+            glow::PixelPackData::BufferOffset(0),
+        );
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+
+        let fence = guard!(
+            gl,
+            gl.fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0)
+                .map_err(Error::Gl)?,
+            move |fence| gl.delete_sync(fence)
+        );
+
+        slot.pending = Some((fence, len, tag));
+        self.next = (self.next + 1) % Self::LEN;
+
+        Ok(true)
+    }
+
+    /// Collect the pixels of the latest completed read into `pixels` without waiting,
+    /// discarding the older completed ones, and returning its tag if any completed.
+    pub unsafe fn collect(&mut self, gl: &Rc<glow::Context>, pixels: &mut Vec<u8>) -> Option<T> {
+        let (next, count) = (self.next, self.slots.len());
+        let mut latest = None;
+
+        // From the oldest pending read to the newest, stopping at the first incomplete one
+        for index in (0..count).map(|offset| (next + offset) % count) {
+            let slot = &mut self.slots[index];
+            let Some((fence, ..)) = &slot.pending else {
+                continue;
+            };
+
+            match gl.client_wait_sync(**fence, 0, 0) {
+                glow::ALREADY_SIGNALED | glow::CONDITION_SATISFIED => {
+                    latest = slot.pending.take().map(|(_, len, tag)| (index, len, tag));
+                }
+                _ => break,
+            }
+        }
+
+        let (index, len, tag) = latest?;
+
+        pixels.resize(len, 0);
+
+        gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(*self.slots[index].buffer));
+        gl.get_buffer_sub_data(glow::PIXEL_PACK_BUFFER, 0, pixels);
+        gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+
+        Some(tag)
+    }
+}
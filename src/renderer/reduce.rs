@@ -0,0 +1,149 @@
+use std::rc::Rc;
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{pass::Pass, readback::Readback, Format};
+use crate::Error;
+
+/// The folding of blocks of texels, summing the red and alpha channels,
+/// and keeping the maximum of the green channel and the minimum of the blue one.
+const FOLD: &str = r#"
+    #version 330 core
+
+    uniform sampler2D _glou_input;
+
+    out vec4 _glou_output;
+
+    vec4 value(ivec2 coord);
+
+    void main() {
+        ivec2 size = textureSize(_glou_input, 0);
+        ivec2 origin = ivec2(gl_FragCoord.xy) * BLOCK;
+        float infinity = uintBitsToFloat(0x7F800000u);
+
+        vec4 folded = vec4(0.0, -infinity, infinity, 0.0);
+        for (int y = origin.y; y < min(origin.y + BLOCK, size.y); y++) {
+            for (int x = origin.x; x < min(origin.x + BLOCK, size.x); x++) {
+                vec4 texel = value(ivec2(x, y));
+
+                folded = vec4(folded.r + texel.r, max(folded.g, texel.g), min(folded.b, texel.b), folded.a + texel.a);
+            }
+        }
+
+        _glou_output = folded;
+    }
+"#;
+
+/// The values of the texels folded past the first pass.
+const IDENTITY: &str = r#"
+    vec4 value(ivec2 coord) {
+        return texelFetch(_glou_input, coord, 0);
+    }
+"#;
+
+/// Reduces textures to a single value on the GPU, through passes folding blocks of texels,
+/// the values being read back asynchronously.
+///
+/// The first pass maps the texels to the folded values with the `vec4 value(ivec2 coord)`
+/// GLSL function, reading from `_glou_input` and the other inputs.
+#[derive(Debug)]
+pub(super) struct Reduction {
+    map: &'static str,
+    first: Option<Pass>,
+    fold: Option<Pass>,
+    readback: Readback<()>,
+    buffer: Vec<u8>,
+}
+
+impl Reduction {
+    /// The width and height of the blocks folded into a single texel.
+    const BLOCK: f32 = 8.0;
+
+    pub fn new(map: &'static str) -> Self {
+        Self {
+            map,
+            first: None,
+            fold: None,
+            readback: Default::default(),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Reduce the `inputs` of `size` to the sum of the red and alpha values, the maximum green value
+    /// and the minimum blue value, letting `setup` set the uniforms of the first pass,
+    /// unless the previous reductions are still being read back.
+    pub unsafe fn reduce(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        size: egui::Vec2,
+        inputs: &[(&str, glow::Texture)],
+        setup: impl FnOnce(glow::Program),
+    ) -> Result<(), Error> {
+        if self.readback.is_full() {
+            return Ok(());
+        }
+
+        // The sums in the alpha channel mustn't be blended
+        gl.disable(glow::BLEND);
+        gl.disable(glow::SCISSOR_TEST);
+
+        let first = match &self.first {
+            Some(first) => first,
+            None => self.first.insert(Self::pass(gl, self.map)?),
+        };
+
+        let mut size = (size / Self::BLOCK).ceil();
+        let mut reduced = first.render_to_texture(gl, size, Format::Rgba32F, inputs, setup)?;
+
+        let fold = match &self.fold {
+            Some(fold) => fold,
+            None => self.fold.insert(Self::pass(gl, IDENTITY)?),
+        };
+
+        while size.x > 1.0 || size.y > 1.0 {
+            size = (size / Self::BLOCK).ceil();
+            reduced = fold.render_to_texture(
+                gl,
+                size,
+                Format::Rgba32F,
+                &[("_glou_input", *reduced)],
+                |_| (),
+            )?;
+        }
+
+        self.readback
+            .read(gl, *reduced, size, Format::Rgba32F, ())?;
+
+        Ok(())
+    }
+
+    /// The latest reduced values read back, if any completed since the last call.
+    pub unsafe fn collect(&mut self, gl: &Rc<glow::Context>) -> Option<[f32; 4]> {
+        self.readback.collect(gl, &mut self.buffer)?;
+
+        let mut values = [0f32; 4];
+        for (value, bytes) in values.iter_mut().zip(self.buffer.chunks_exact(4)) {
+            *value = f32::from_ne_bytes(bytes.try_into().expect("Mis-sized float"));
+        }
+
+        Some(values)
+    }
+
+    /// Drop the reductions being read back.
+    pub fn reset(&mut self) {
+        self.readback = Default::default();
+    }
+
+    unsafe fn pass(gl: &Rc<glow::Context>, map: &str) -> Result<Pass, Error> {
+        let source = FOLD.replacen(
+            "#version 330 core",
+            &format!("#version 330 core\n#define BLOCK {}", Self::BLOCK as i32),
+            1,
+        );
+
+        Pass::new(gl, &format!("{source}{map}"))
+    }
+}
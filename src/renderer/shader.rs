@@ -4,19 +4,21 @@ use std::{
     time::{self, SystemTime},
 };
 
-use eframe::{
-    egui,
-    glow::{self, HasContext},
-};
+use glow::HasContext;
 
-use super::Uniforms;
+use super::{target, Format, Uniforms};
 use crate::{guard, AllocGuard, Error};
 
+pub(super) type Program = (AllocGuard<glow::Program>, AllocGuard<glow::VertexArray>);
+
 #[derive(Debug)]
 pub struct Shader {
     path: PathBuf,
+    wrapper: fn(&str) -> String,
     rebuilt_at: f64,
-    inner: Option<(AllocGuard<glow::Program>, AllocGuard<glow::VertexArray>)>,
+    source: String,
+    parameters: Vec<String>,
+    inner: Option<Program>,
 }
 
 impl Shader {
@@ -31,18 +33,64 @@ impl Shader {
     "#;
 
     pub fn new(path: PathBuf) -> Self {
+        Self::with_wrapper(path, str::to_string)
+    }
+
+    /// Create a shader whose source is transformed by `wrapper` before compilation.
+    pub fn with_wrapper(path: PathBuf, wrapper: fn(&str) -> String) -> Self {
         Self {
             path,
+            wrapper,
             rebuilt_at: 0f64,
+            source: String::new(),
+            parameters: Vec::new(),
             inner: None,
         }
     }
 
+    /// Create a shader from an in-memory `source`, without any file, compiled on the first rebuild.
+    pub fn from_source(source: String) -> Self {
+        Self {
+            source,
+            ..Self::new(PathBuf::new())
+        }
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
 
-    pub fn rebuild(&mut self, gl: &Rc<glow::Context>) -> Result<bool, Error> {
+    /// The source of the shader, as last read from the file.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    #[cfg(feature = "gui")]
+    pub(super) fn inner(&self) -> Option<&Program> {
+        self.inner.as_ref()
+    }
+
+    /// The names of the `float` uniforms declared by the shader.
+    pub fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+
+    /// Read the source again if the file changed, without compiling it, returning whether it changed.
+    pub fn reload(&mut self) -> Result<bool, Error> {
+        // The in-memory sources never change, and are read once
+        if self.path.as_os_str().is_empty() {
+            if self.rebuilt_at > 0f64 {
+                return Ok(false);
+            }
+
+            self.rebuilt_at = SystemTime::now()
+                .duration_since(time::UNIX_EPOCH)
+                .expect("Time went backwards >.>")
+                .as_secs_f64();
+
+            return Ok(true);
+        }
+
         if std::fs::metadata(&self.path)?
             .modified()?
             .duration_since(time::UNIX_EPOCH)
@@ -51,7 +99,7 @@ impl Shader {
             > self.rebuilt_at
         {
             tracing::info!(
-                "Source file at `{}` was updated, compiling shader..",
+                "Source file at `{}` was updated, reading shader..",
                 self.path.display()
             );
 
@@ -60,42 +108,81 @@ impl Shader {
                 .expect("Time went backwards >.>")
                 .as_secs_f64();
 
-            let source = std::fs::read_to_string(&self.path)?;
+            self.source = std::fs::read_to_string(&self.path)?;
 
-            unsafe {
-                let program = guard!(
-                    gl,
-                    gl.create_program().map_err(Error::Gl)?,
-                    move |program| gl.delete_program(program)
-                );
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
 
-                let vert = Self::shader(gl, glow::VERTEX_SHADER, Self::VERTEX)?;
-                let frag = Self::shader(gl, glow::FRAGMENT_SHADER, &source)?;
+    /// Read the source again and compile it if the file changed, returning whether it changed.
+    pub fn rebuild(&mut self, gl: &Rc<glow::Context>) -> Result<bool, Error> {
+        if !self.reload()? {
+            return Ok(false);
+        }
 
-                gl.attach_shader(*program, *vert);
-                gl.attach_shader(*program, *frag);
-                gl.link_program(*program);
-                gl.detach_shader(*program, *vert);
-                gl.detach_shader(*program, *frag);
+        unsafe { self.load(gl)? };
 
-                let vertices = guard!(
-                    gl,
-                    gl.create_vertex_array().map_err(Error::Gl)?,
-                    move |vertices| gl.delete_vertex_array(vertices)
-                );
+        tracing::info!(
+            "Successfully compiled shader from `{}`",
+            self.path.display()
+        );
 
-                self.inner = Some((program, vertices));
+        Ok(true)
+    }
 
-                tracing::info!(
-                    "Successfully compiled loaded new shader from `{}`",
-                    self.path.display()
-                );
-            }
+    /// Read and compile the source again on the next rebuild, even if the file didn't change.
+    pub fn reset(&mut self) {
+        self.rebuilt_at = 0f64;
+    }
 
-            Ok(true)
-        } else {
-            Ok(false)
+    /// Compile the current source, and list its `float` uniforms.
+    unsafe fn load(&mut self, gl: &Rc<glow::Context>) -> Result<(), Error> {
+        let (program, vertices) = Self::compile(gl, &(self.wrapper)(&self.source))?;
+
+        self.parameters = (0..gl.get_active_uniforms(*program))
+            .filter_map(|index| gl.get_active_uniform(*program, index))
+            .filter(|uniform| uniform.utype == glow::FLOAT && uniform.size == 1)
+            .map(|uniform| uniform.name)
+            .collect();
+
+        self.inner = Some((program, vertices));
+
+        Ok(())
+    }
+
+    /// Compile and link a fragment shader `source` along with the fullscreen vertex shader.
+    pub(super) unsafe fn compile(gl: &Rc<glow::Context>, source: &str) -> Result<Program, Error> {
+        let program = guard!(
+            gl,
+            gl.create_program().map_err(Error::Gl)?,
+            move |program| gl.delete_program(program)
+        );
+
+        let vert = Self::shader(gl, glow::VERTEX_SHADER, Self::VERTEX)?;
+        let frag = Self::shader(gl, glow::FRAGMENT_SHADER, source)?;
+
+        gl.attach_shader(*program, *vert);
+        gl.attach_shader(*program, *frag);
+        gl.link_program(*program);
+        gl.detach_shader(*program, *vert);
+        gl.detach_shader(*program, *frag);
+
+        if !gl.get_program_link_status(*program) {
+            return Err(Error::Compile(format!(
+                "Failed to link shader:\n{}",
+                gl.get_program_info_log(*program),
+            )));
         }
+
+        let vertices = guard!(
+            gl,
+            gl.create_vertex_array().map_err(Error::Gl)?,
+            move |vertices| gl.delete_vertex_array(vertices)
+        );
+
+        Ok((program, vertices))
     }
 
     unsafe fn shader(
@@ -122,68 +209,35 @@ impl Shader {
         }
     }
 
-    unsafe fn render(&self, gl: &Rc<glow::Context>, uniforms: &Uniforms) {
-        if let Some((program, vertices)) = &self.inner {
-            gl.use_program(Some(**program));
+    /// Draw a fullscreen quad with `program` in the currently bound framebuffer.
+    pub(super) unsafe fn draw(gl: &Rc<glow::Context>, program: &Program, uniforms: &Uniforms) {
+        let (program, vertices) = program;
 
-            uniforms.apply(gl, **program);
+        gl.use_program(Some(**program));
 
-            gl.bind_vertex_array(Some(**vertices));
-            gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
-            gl.bind_vertex_array(None);
-        }
+        uniforms.apply(gl, **program);
+
+        gl.bind_vertex_array(Some(**vertices));
+        gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+        gl.bind_vertex_array(None);
     }
 
+    /// Render the shader with the `uniforms` to a texture of `size` and `format` from the [`super::Pool`].
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
     pub unsafe fn render_to_texture(
         &self,
         gl: &Rc<glow::Context>,
         uniforms: &Uniforms,
-        size: egui::Vec2,
+        size: [f32; 2],
+        format: Format,
     ) -> Result<AllocGuard<glow::Texture>, Error> {
-        let texture = guard!(
-            gl,
-            gl.create_texture().map_err(Error::Gl)?,
-            move |texture| gl.delete_texture(texture)
-        );
-
-        gl.bind_texture(glow::TEXTURE_2D, Some(*texture));
-        gl.tex_image_2d(
-            glow::TEXTURE_2D,
-            0,
-            glow::RGBA as i32,
-            size.x as i32,
-            size.y as i32,
-            0,
-            glow::RGBA,
-            glow::UNSIGNED_BYTE,
-            None,
-        );
-
-        let buffer = guard!(
-            gl,
-            gl.create_framebuffer().map_err(Error::Gl)?,
-            move |buffer| gl.delete_framebuffer(buffer)
-        );
-
-        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(*buffer));
-        gl.framebuffer_texture_2d(
-            glow::FRAMEBUFFER,
-            glow::COLOR_ATTACHMENT0,
-            glow::TEXTURE_2D,
-            Some(*texture),
-            0,
-        );
-        gl.draw_buffer(glow::COLOR_ATTACHMENT0);
-
-        assert!(gl.check_framebuffer_status(glow::FRAMEBUFFER) == glow::FRAMEBUFFER_COMPLETE);
-
-        gl.viewport(0, 0, size.x as i32, size.y as i32);
-
-        self.render(gl, uniforms);
-
-        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
-        gl.bind_texture(glow::TEXTURE_2D, None);
-
-        Ok(texture)
+        target::render_to_texture(gl, size, format, || {
+            if let Some(program) = &self.inner {
+                Self::draw(gl, program, uniforms);
+            }
+        })
     }
 }
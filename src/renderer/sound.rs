@@ -0,0 +1,233 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+use eframe::glow::{self, HasContext};
+
+use super::{target, Format, Shader, Uniforms};
+use crate::{
+    audio::{Player, Track},
+    Error,
+};
+
+/// A sound shader, synthesizing audio on the GPU through a `vec2 mainSound(int samp, float time)` function.
+#[derive(Debug)]
+pub struct Sound {
+    shader: Shader,
+    player: Option<Player>,
+    stale: bool,
+    /// The samples of the playback buffer rendered so far, a block per update.
+    pending: Option<Vec<f32>>,
+    /// The path of the requested export, with the samples rendered so far, a block per update.
+    export: Option<(PathBuf, Vec<f32>)>,
+
+    /// The length of the rendered playback buffer, in seconds.
+    pub duration: f32,
+    /// The length of the exported audio files, in seconds.
+    pub length: f32,
+}
+
+impl Sound {
+    /// The sample rate of the synthesized audio.
+    pub const RATE: u32 = 44100;
+
+    /// The size of the square texture each block of samples is rendered into.
+    const BLOCK: i32 = 512;
+
+    const HEADER: &'static str = r#"
+        #version 330 core
+
+        uniform int _glou_offset;
+        uniform int _glou_width;
+        uniform float _glou_rate;
+
+        out vec4 _glou_output;
+    "#;
+
+    const FOOTER: &'static str = r#"
+        void main() {
+            int samp = _glou_offset + int(gl_FragCoord.y) * _glou_width + int(gl_FragCoord.x);
+
+            _glou_output = vec4(mainSound(samp, float(samp) / _glou_rate), 0.0, 1.0);
+        }
+    "#;
+
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            shader: Shader::with_wrapper(path, Self::wrap),
+            player: None,
+            stale: true,
+            pending: None,
+            export: None,
+            duration: 60.0,
+            length: 10.0,
+        }
+    }
+
+    fn wrap(source: &str) -> String {
+        // The version line is blanked rather than removed, for the errors to point at the right lines
+        let source = source
+            .lines()
+            .map(|line| match line.trim_start().starts_with("#version") {
+                true => "",
+                false => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("{}\n#line 1\n{source}\n{}", Self::HEADER, Self::FOOTER)
+    }
+
+    pub fn path(&self) -> &Path {
+        self.shader.path()
+    }
+
+    /// Re-render the playback buffer on the next update.
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Export the next `length` seconds of audio to a WAV file at `path`, rendered over the next updates.
+    pub fn request_export(&mut self, path: PathBuf) {
+        tracing::info!(
+            "Exporting {}s of sound shader to `{}`",
+            self.length,
+            path.display()
+        );
+
+        self.export = Some((path, Vec::new()));
+    }
+
+    /// Rebuild the shader if needed, render the next block of the playback buffer once stale,
+    /// keep the playback in sync with the `time` uniform and render the next block of the pending export.
+    pub fn update(&mut self, gl: &Rc<glow::Context>, uniforms: &Uniforms) -> Result<(), Error> {
+        if self.shader.rebuild(gl)? {
+            self.stale = true;
+        }
+
+        if std::mem::take(&mut self.stale) {
+            self.player = None;
+            self.pending = self.shader.inner().is_some().then(Vec::new);
+        }
+
+        if let Some(mut samples) = self.pending.take() {
+            let frames = Self::frames(self.duration);
+
+            unsafe { self.render(gl, uniforms, Self::next(&samples, frames), &mut samples)? };
+
+            if samples.len() / 2 < frames {
+                self.pending = Some(samples);
+            } else {
+                let track = Track::new(samples, 2, Self::RATE);
+                self.player = Some(Player::new(Arc::new(track))?);
+            }
+        }
+
+        if let Some(player) = &self.player {
+            player.sync(uniforms.time());
+        }
+
+        if let Some((path, mut samples)) = self.export.take() {
+            let frames = Self::frames(self.length);
+
+            unsafe { self.render(gl, uniforms, Self::next(&samples, frames), &mut samples)? };
+
+            if samples.len() / 2 < frames {
+                self.export = Some((path, samples));
+            } else {
+                let track = Track::new(samples, 2, Self::RATE);
+
+                let mut writer = hound::WavWriter::create(
+                    path,
+                    hound::WavSpec {
+                        channels: track.channels() as u16,
+                        sample_rate: track.rate(),
+                        bits_per_sample: 16,
+                        sample_format: hound::SampleFormat::Int,
+                    },
+                )?;
+                for sample in track.samples() {
+                    writer.write_sample((sample * i16::MAX as f32) as i16)?;
+                }
+                writer.finalize()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The number of stereo frames in `length` seconds of audio.
+    fn frames(length: f32) -> usize {
+        (length.max(0.0) * Self::RATE as f32) as usize
+    }
+
+    /// The number of stereo frames once the block following the stereo `samples` is rendered, up to `frames`.
+    fn next(samples: &[f32], frames: usize) -> usize {
+        frames.min(samples.len() / 2 + (Self::BLOCK * Self::BLOCK) as usize)
+    }
+
+    /// Render the audio following the stereo `samples` up to `frames`, block by block,
+    /// reading the samples back from the GPU.
+    unsafe fn render(
+        &self,
+        gl: &Rc<glow::Context>,
+        uniforms: &Uniforms,
+        frames: usize,
+        samples: &mut Vec<f32>,
+    ) -> Result<(), Error> {
+        let Some((program, vertices)) = self.shader.inner() else {
+            return Err(Error::Compile("The sound shader is not compiled".into()));
+        };
+
+        let block = (Self::BLOCK * Self::BLOCK) as usize;
+
+        samples.reserve(frames.saturating_sub(samples.len() / 2) * 2);
+
+        target::render_to_texture(gl, [Self::BLOCK as f32; 2], Format::Rgba32F, || {
+            gl.disable(glow::BLEND);
+            gl.disable(glow::SCISSOR_TEST);
+
+            gl.use_program(Some(**program));
+
+            let location = |name: &str| gl.get_uniform_location(**program, name);
+            gl.uniform_1_i32(location("_glou_width").as_ref(), Self::BLOCK);
+            gl.uniform_1_f32(location("_glou_rate").as_ref(), Self::RATE as f32);
+            gl.uniform_1_f32(
+                location(&uniforms.style.format("sampleRate")).as_ref(),
+                Self::RATE as f32,
+            );
+
+            gl.bind_vertex_array(Some(**vertices));
+
+            let mut pixels = vec![0f32; block * 4];
+
+            for offset in (samples.len() / 2..frames).step_by(block) {
+                gl.uniform_1_i32(location("_glou_offset").as_ref(), offset as i32);
+                gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+
+                gl.read_pixels(
+                    0,
+                    0,
+                    Self::BLOCK,
+                    Self::BLOCK,
+                    glow::RGBA,
+                    glow::FLOAT,
+                    glow::PixelPackData::Slice(bytemuck::cast_slice_mut(&mut pixels)),
+                );
+
+                samples.extend(
+                    pixels
+                        .chunks(4)
+                        .take(frames - offset)
+                        .flat_map(|pixel| [pixel[0].clamp(-1.0, 1.0), pixel[1].clamp(-1.0, 1.0)]),
+                );
+            }
+
+            gl.bind_vertex_array(None);
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+use std::{collections::BTreeSet, rc::Rc};
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+
+use super::{
+    interpreter::{Inputs, Program, Step, Texture, Trace},
+    Format, Inspection, Shader, Uniforms,
+};
+use crate::{guard, Error};
+
+/// Steps through the invocation of the fragment shader for a single pixel, interpreted on the CPU.
+#[derive(Debug, Default)]
+pub struct Stepper {
+    pub enabled: bool,
+    /// The lines to stop at when continuing.
+    pub breakpoints: BTreeSet<usize>,
+
+    /// The debugged pixel, in OpenGL coordinates.
+    pixel: Option<egui::Pos2>,
+    requested: bool,
+
+    source: String,
+    program: Option<Program>,
+
+    trace: Option<Trace>,
+    position: usize,
+    /// The color rendered by the GPU for the pixel.
+    rendered: Option<[f32; 4]>,
+    /// Whether the rendered color was clamped by a fixed-point render target.
+    clamped: bool,
+    /// The error which interrupted the last interpretation.
+    error: Option<String>,
+}
+
+impl Stepper {
+    /// The maximum relative difference between the interpreted and rendered colors still considered matching,
+    /// accounting for the 8-bit quantization of the rendered texture, and the rounding of the half floats above one.
+    pub const TOLERANCE: f32 = 1.5 / 255.0;
+
+    /// Reparse the shader for interpretation if it changed, returning whether it changed.
+    pub fn update(&mut self, shader: Option<&Shader>) -> Result<bool, Error> {
+        let source = match shader {
+            Some(shader) if self.enabled => shader.source(),
+            _ => "",
+        };
+
+        if source == self.source {
+            return Ok(false);
+        }
+
+        self.source = source.to_string();
+        self.program = None;
+        self.trace = None;
+        self.requested = self.pixel.is_some();
+
+        if !self.source.is_empty() {
+            self.program = Some(Program::parse(&self.source).map_err(|err| {
+                Error::Interpret(format!("Unable to interpret the shader on the CPU:\n{err}"))
+            })?);
+        }
+
+        Ok(true)
+    }
+
+    /// Request the interpretation of the shader for `pixel`, in OpenGL coordinates.
+    pub fn select(&mut self, pixel: egui::Pos2) {
+        self.pixel = Some(pixel);
+        self.requested = true;
+    }
+
+    pub fn pixel(&self) -> Option<egui::Pos2> {
+        self.pixel
+    }
+
+    /// Interpret the shader for the selected pixel if requested, reading back the rendered `texture` of size `size` and `format`.
+    pub(super) unsafe fn run(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        uniforms: &Uniforms,
+        texture: glow::Texture,
+        size: egui::Vec2,
+        format: Format,
+    ) -> Result<(), Error> {
+        let (true, Some(program), Some(pixel)) = (self.requested, &self.program, self.pixel) else {
+            return Ok(());
+        };
+        self.requested = false;
+
+        self.rendered = Some(Inspection::read(gl, texture, size, pixel)?.rgba);
+        self.clamped = format == Format::Rgba8;
+
+        let inputs = Inputs {
+            coord: [pixel.x + 0.5, pixel.y + 0.5],
+            uniforms: uniforms.to_iter().collect(),
+            textures: uniforms
+                .textures()
+                .map(|(name, texture, size)| Ok((name, Self::download(gl, texture, size)?)))
+                .collect::<Result<_, Error>>()?,
+        };
+
+        self.position = 0;
+        (self.trace, self.error) = match program.run(&inputs) {
+            Ok(trace) => (Some(trace), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+
+        Ok(())
+    }
+
+    /// Read back the whole `texture` of size `size` for sampling by the interpreter.
+    unsafe fn download(
+        gl: &Rc<glow::Context>,
+        texture: glow::Texture,
+        size: [f32; 2],
+    ) -> Result<Texture, Error> {
+        let (width, height) = (size[0] as usize, size[1] as usize);
+        let mut texels = vec![[0f32; 4]; width * height];
+
+        let buffer = guard!(
+            gl,
+            gl.create_framebuffer().map_err(Error::Gl)?,
+            move |buffer| gl.delete_framebuffer(buffer)
+        );
+
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(*buffer));
+        gl.framebuffer_texture_2d(
+            glow::READ_FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(texture),
+            0,
+        );
+        gl.read_buffer(glow::COLOR_ATTACHMENT0);
+        gl.read_pixels(
+            0,
+            0,
+            width as i32,
+            height as i32,
+            glow::RGBA,
+            glow::FLOAT,
+            glow::PixelPackData::Slice(bytemuck::cast_slice_mut(&mut texels)),
+        );
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+
+        Ok(Texture {
+            width,
+            height,
+            texels,
+        })
+    }
+
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn rendered(&self) -> Option<[f32; 4]> {
+        self.rendered
+    }
+
+    /// The index of the current step, equal to the number of steps once finished.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The current step, or `None` once finished.
+    pub fn step(&self) -> Option<&Step> {
+        self.trace.as_ref()?.steps.get(self.position)
+    }
+
+    /// The maximum difference between the interpreted color, clamped like the rendered one, and the rendered color,
+    /// relative to the magnitude of the rendered color above one as the floating-point formats lose precision there.
+    pub fn difference(&self) -> Option<f32> {
+        let (output, rendered) = (self.trace.as_ref()?.output?, self.rendered?);
+
+        Some(
+            output
+                .iter()
+                .zip(rendered)
+                .map(|(output, rendered)| {
+                    let output = if self.clamped {
+                        output.clamp(0.0, 1.0)
+                    } else {
+                        *output
+                    };
+
+                    (output - rendered).abs() / rendered.abs().max(1.0)
+                })
+                .fold(0.0, f32::max),
+        )
+    }
+
+    /// Move forward to the next step satisfying `stop`, or to the end.
+    fn advance(&mut self, stop: impl Fn(&Step) -> bool) {
+        let Some(trace) = &self.trace else {
+            return;
+        };
+
+        self.position = trace
+            .steps
+            .iter()
+            .enumerate()
+            .skip(self.position + 1)
+            .find(|(_, step)| stop(step))
+            .map(|(index, _)| index)
+            .unwrap_or(trace.steps.len());
+    }
+
+    pub fn restart(&mut self) {
+        self.position = 0;
+    }
+
+    pub fn step_back(&mut self) {
+        self.position = self.position.saturating_sub(1);
+    }
+
+    pub fn step_into(&mut self) {
+        self.advance(|_| true);
+    }
+
+    /// Step to the next statement, without entering function calls.
+    pub fn step_over(&mut self) {
+        let depth = self.step().map(Step::depth).unwrap_or_default();
+
+        self.advance(|step| step.depth() <= depth);
+    }
+
+    /// Step until the current function returns.
+    pub fn step_out(&mut self) {
+        let depth = self.step().map(Step::depth).unwrap_or_default();
+
+        self.advance(|step| step.depth() < depth);
+    }
+
+    /// Run until a line with a breakpoint is reached.
+    pub fn resume(&mut self) {
+        let breakpoints = self.breakpoints.clone();
+
+        self.advance(|step| breakpoints.contains(&step.line()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "#version 330 core
+out vec4 color;
+float twice(float x) {
+    float y = x * 2.0;
+    return y;
+}
+void main() {
+    float a = gl_FragCoord.x;
+    float b = twice(a);
+    color = vec4(b, 0.0, 0.0, 1.0);
+}";
+
+    /// A stepper at the start of the interpretation of [`SOURCE`] for the pixel at `coord`.
+    fn stepper(coord: [f32; 2]) -> Stepper {
+        let program = Program::parse(SOURCE).expect("Unable to parse the shader");
+        let trace = program
+            .run(&Inputs {
+                coord,
+                ..Default::default()
+            })
+            .expect("Unable to interpret the shader");
+
+        Stepper {
+            trace: Some(trace),
+            ..Default::default()
+        }
+    }
+
+    fn line(stepper: &Stepper) -> Option<usize> {
+        stepper.step().map(Step::line)
+    }
+
+    #[test]
+    fn stepping() {
+        let mut stepper = stepper([0.5, 0.5]);
+        assert_eq!(line(&stepper), Some(8));
+
+        stepper.step_into();
+        assert_eq!(line(&stepper), Some(9));
+        // Entering `twice` at its signature, where its argument is stored
+        stepper.step_into();
+        assert_eq!(line(&stepper), Some(3));
+        assert_eq!(stepper.step().map(Step::depth), Some(2));
+        stepper.step_into();
+        assert_eq!(line(&stepper), Some(4));
+
+        // Back in `main`, storing the returned value
+        stepper.step_out();
+        assert_eq!(line(&stepper), Some(9));
+        assert_eq!(stepper.step().map(Step::depth), Some(1));
+
+        stepper.step_back();
+        assert_eq!(line(&stepper), Some(5));
+        stepper.restart();
+        assert_eq!(stepper.position(), 0);
+
+        stepper.step_over();
+        stepper.step_over();
+        assert_eq!(line(&stepper), Some(9));
+        stepper.step_over();
+        assert_eq!(line(&stepper), Some(10));
+
+        stepper.step_over();
+        assert_eq!(line(&stepper), None);
+        assert_eq!(stepper.position(), stepper.trace().unwrap().steps.len());
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut stepper = stepper([0.5, 0.5]);
+        stepper.breakpoints.insert(5);
+        stepper.breakpoints.insert(10);
+
+        stepper.resume();
+        assert_eq!(line(&stepper), Some(5));
+        assert_eq!(
+            stepper.step().unwrap().frames.last().unwrap().function,
+            "twice"
+        );
+
+        stepper.resume();
+        assert_eq!(line(&stepper), Some(10));
+
+        stepper.resume();
+        assert_eq!(line(&stepper), None);
+    }
+
+    #[test]
+    fn difference() {
+        let mut stepper = stepper([2.5, 0.5]);
+        assert_eq!(stepper.difference(), None);
+
+        // The interpreted red of 5 is clamped by fixed-point targets
+        stepper.rendered = Some([1.0, 0.0, 0.0, 1.0]);
+        stepper.clamped = true;
+        assert_eq!(stepper.difference(), Some(0.0));
+
+        // And compared relatively by floating-point ones
+        stepper.rendered = Some([5.01, 0.0, 0.0, 1.0]);
+        stepper.clamped = false;
+        assert!(stepper.difference().unwrap() < Stepper::TOLERANCE);
+
+        stepper.rendered = Some([4.0, 0.0, 0.0, 1.0]);
+        assert!(stepper.difference().unwrap() > Stepper::TOLERANCE);
+    }
+}
@@ -0,0 +1,136 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Comparison, Debugger, Display, Encoding, Format, Heatmap, Inspector, Navigation, Shader,
+    Stepper, Tonemapper, UniformStyle, Uniforms,
+};
+use crate::{canvas::View, Error};
+
+/// The errors of a tab, kept along with it while inactive.
+#[derive(Debug, Default)]
+pub struct Errors {
+    /// The error of the shader, or of the last failed action.
+    pub main: Option<Error>,
+    /// The errors of the tools by name, kept until they update successfully.
+    pub tools: BTreeMap<&'static str, Error>,
+}
+
+/// The state of a shader tab, held by the [`super::Renderer`] while the tab is active.
+#[derive(Debug, Default)]
+pub(super) struct Tab {
+    pub shader: Option<Shader>,
+    pub uniforms: Uniforms,
+    pub size: eframe::egui::Vec2,
+    pub resizable: bool,
+    pub format: Format,
+    pub display: Display,
+    pub view: View,
+    pub navigation: Navigation,
+    pub inspector: Inspector,
+    pub debugger: Debugger,
+    pub stepper: Stepper,
+    pub heatmap: Heatmap,
+    pub comparison: Comparison,
+    pub errors: Errors,
+}
+
+impl Tab {
+    /// The title of the tab, the file name of its shader.
+    pub fn title(shader: Option<&Shader>) -> String {
+        shader
+            .and_then(|shader| shader.path().file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_string())
+    }
+}
+
+/// The persisted state of a tab, restored on restart.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Saved {
+    path: Option<PathBuf>,
+    style: UniformStyle,
+    parameters: BTreeMap<String, f32>,
+    size: [f32; 2],
+    resizable: bool,
+    format: Format,
+    source: Encoding,
+    canvas: Encoding,
+    output: Encoding,
+    exposure: f32,
+    tonemapper: Tonemapper,
+}
+
+impl From<&Tab> for Saved {
+    fn from(tab: &Tab) -> Self {
+        Self {
+            path: tab
+                .shader
+                .as_ref()
+                .map(|shader| shader.path().to_path_buf()),
+            style: tab.uniforms.style,
+            parameters: tab.uniforms.parameters.clone(),
+            size: tab.size.into(),
+            resizable: tab.resizable,
+            format: tab.format,
+            source: tab.display.source,
+            canvas: tab.display.canvas,
+            output: tab.display.output,
+            exposure: tab.display.exposure,
+            tonemapper: tab.display.tonemapper,
+        }
+    }
+}
+
+impl From<Saved> for Tab {
+    fn from(saved: Saved) -> Self {
+        let mut tab = Self {
+            shader: saved.path.map(Shader::new),
+            size: saved.size.into(),
+            resizable: saved.resizable,
+            format: saved.format,
+            ..Default::default()
+        };
+
+        tab.uniforms.style = saved.style;
+        tab.uniforms.parameters = saved.parameters;
+
+        tab.display.source = saved.source;
+        tab.display.canvas = saved.canvas;
+        tab.display.output = saved.output;
+        tab.display.exposure = saved.exposure;
+        tab.display.tonemapper = saved.tonemapper;
+
+        tab
+    }
+}
+
+/// The persisted tabs of the [`super::Renderer`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    tabs: Vec<Saved>,
+    active: usize,
+}
+
+impl Session {
+    pub(super) fn new(tabs: &[Tab], active: usize) -> Self {
+        Self {
+            tabs: tabs.iter().map(Saved::from).collect(),
+            active,
+        }
+    }
+
+    /// The restored tabs, with the index of the active one, if any.
+    pub(super) fn restore(self) -> Option<(Vec<Tab>, usize)> {
+        if self.tabs.is_empty() {
+            return None;
+        }
+
+        let active = self.active.min(self.tabs.len() - 1);
+
+        Some((self.tabs.into_iter().map(Tab::from).collect(), active))
+    }
+}
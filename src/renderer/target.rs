@@ -0,0 +1,263 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use glow::HasContext;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter};
+
+use crate::{guard, AllocGuard, Error};
+
+/// The pixel format of a render target.
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr, Serialize, Deserialize)]
+pub enum Format {
+    #[default]
+    #[strum(serialize = "RGBA8")]
+    Rgba8,
+    #[strum(serialize = "RGBA16F")]
+    Rgba16F,
+    #[strum(serialize = "RGBA32F")]
+    Rgba32F,
+}
+
+impl Format {
+    pub(super) fn internal(&self) -> u32 {
+        match self {
+            Self::Rgba8 => glow::RGBA8,
+            Self::Rgba16F => glow::RGBA16F,
+            Self::Rgba32F => glow::RGBA32F,
+        }
+    }
+
+    pub(crate) fn ty(&self) -> u32 {
+        match self {
+            Self::Rgba8 => glow::UNSIGNED_BYTE,
+            Self::Rgba16F => glow::HALF_FLOAT,
+            Self::Rgba32F => glow::FLOAT,
+        }
+    }
+
+    /// The size of a pixel, in bytes.
+    pub fn bytes(&self) -> usize {
+        match self {
+            Self::Rgba8 => 4,
+            Self::Rgba16F => 8,
+            Self::Rgba32F => 16,
+        }
+    }
+}
+
+/// A render target of the [`Pool`], released for reuse since the frame in `released`.
+#[derive(Debug)]
+struct Target {
+    framebuffer: glow::Framebuffer,
+    size: (i32, i32),
+    format: Format,
+    released: Option<u64>,
+}
+
+/// The render targets allocated on the context of this thread, by texture.
+#[derive(Debug, Default)]
+struct Targets {
+    targets: HashMap<glow::Texture, Target>,
+    frame: u64,
+    /// The number of the external frame last trimmed at.
+    trimmed: Option<u64>,
+}
+
+thread_local! {
+    static TARGETS: RefCell<Targets> = RefCell::default();
+}
+
+/// The pool of render targets, the textures and their framebuffers being reused across frames
+/// as long as the same sizes and formats are rendered.
+#[derive(Debug)]
+pub struct Pool;
+
+impl Pool {
+    /// The frames a released target is kept for, before being deleted.
+    const KEEP: u64 = 2;
+
+    /// Reuse a released target of `size` and `format`, or allocate a new one.
+    unsafe fn acquire(
+        gl: &Rc<glow::Context>,
+        size: [f32; 2],
+        format: Format,
+    ) -> Result<(glow::Texture, glow::Framebuffer), Error> {
+        let size = (size[0] as i32, size[1] as i32);
+
+        let reused = TARGETS.with_borrow_mut(|pool| {
+            pool.targets
+                .iter_mut()
+                .find(|(_, target)| {
+                    target.released.is_some() && target.size == size && target.format == format
+                })
+                .map(|(texture, target)| {
+                    target.released = None;
+
+                    (*texture, target.framebuffer)
+                })
+        });
+        if let Some(reused) = reused {
+            return Ok(reused);
+        }
+
+        let texture = gl.create_texture().map_err(Error::Gl)?;
+        let texture = guard!(gl, texture, move |texture| gl.delete_texture(texture));
+
+        gl.bind_texture(glow::TEXTURE_2D, Some(*texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            format.internal() as i32,
+            size.0,
+            size.1,
+            0,
+            glow::RGBA,
+            format.ty(),
+            None,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::NEAREST as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            glow::NEAREST as i32,
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        let framebuffer = gl.create_framebuffer().map_err(Error::Gl)?;
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(*texture),
+            0,
+        );
+        gl.draw_buffer(glow::COLOR_ATTACHMENT0);
+
+        assert!(gl.check_framebuffer_status(glow::FRAMEBUFFER) == glow::FRAMEBUFFER_COMPLETE);
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+
+        let texture = AllocGuard::into_inner(texture);
+        TARGETS.with_borrow_mut(|pool| {
+            pool.targets.insert(
+                texture,
+                Target {
+                    framebuffer,
+                    size,
+                    format,
+                    released: None,
+                },
+            )
+        });
+
+        Ok((texture, framebuffer))
+    }
+
+    /// The framebuffer the `texture` is attached to, if it is a render target.
+    pub fn framebuffer(texture: glow::Texture) -> Option<glow::Framebuffer> {
+        TARGETS.with_borrow(|pool| pool.targets.get(&texture).map(|target| target.framebuffer))
+    }
+
+    /// Release the `texture` for reuse if it is a render target, or delete it.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
+    pub unsafe fn release(gl: &Rc<glow::Context>, texture: glow::Texture) {
+        let known = TARGETS.with_borrow_mut(|pool| {
+            let frame = pool.frame;
+
+            pool.targets
+                .get_mut(&texture)
+                .map(|target| target.released = Some(frame))
+                .is_some()
+        });
+
+        if !known {
+            gl.delete_texture(texture);
+        }
+    }
+
+    /// Advance to the next frame, deleting the targets left unused for a few frames,
+    /// such as the ones of a previous viewport size.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
+    pub unsafe fn trim(gl: &Rc<glow::Context>) {
+        TARGETS.with_borrow_mut(|pool| {
+            let frame = pool.frame;
+
+            pool.targets
+                .retain(|texture, target| match target.released {
+                    Some(released) if frame - released >= Self::KEEP => {
+                        gl.delete_framebuffer(target.framebuffer);
+                        gl.delete_texture(*texture);
+
+                        false
+                    }
+                    _ => true,
+                });
+            pool.frame += 1;
+        });
+    }
+
+    /// Delete all the released targets, before the context of this thread goes away.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
+    pub unsafe fn clear(gl: &Rc<glow::Context>) {
+        TARGETS.with_borrow_mut(|pool| {
+            pool.targets.retain(|texture, target| {
+                if target.released.is_none() {
+                    return true;
+                }
+
+                gl.delete_framebuffer(target.framebuffer);
+                gl.delete_texture(*texture);
+
+                false
+            })
+        });
+    }
+
+    /// Trim once for the frame numbered `frame`, for the callers sharing a frame loop they don't own.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
+    pub unsafe fn trim_once(gl: &Rc<glow::Context>, frame: u64) {
+        if TARGETS.with_borrow_mut(|pool| pool.trimmed.replace(frame) != Some(frame)) {
+            Self::trim(gl);
+        }
+    }
+}
+
+/// Render into a texture of the provided `size` and `format` from the [`Pool`] using `draw`,
+/// the texture being released back to the pool once dropped.
+pub(super) unsafe fn render_to_texture(
+    gl: &Rc<glow::Context>,
+    size: [f32; 2],
+    format: Format,
+    draw: impl FnOnce(),
+) -> Result<AllocGuard<glow::Texture>, Error> {
+    let (texture, framebuffer) = Pool::acquire(gl, size, format)?;
+    let texture = guard!(gl, texture, move |texture| Pool::release(&gl, texture));
+
+    gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+    gl.viewport(0, 0, size[0] as i32, size[1] as i32);
+
+    draw();
+
+    gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    gl.bind_texture(glow::TEXTURE_2D, None);
+
+    Ok(texture)
+}
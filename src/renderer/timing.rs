@@ -0,0 +1,236 @@
+use std::{
+    collections::{vec_deque, VecDeque},
+    rc::Rc,
+    time,
+};
+
+use eframe::glow::{self, HasContext};
+
+use crate::{guard, AllocGuard, Error};
+
+/// The timings of a single frame, in milliseconds.
+#[derive(Debug, Default, Clone)]
+pub struct Frame {
+    /// The time elapsed since the previous frame.
+    pub interval: f32,
+    /// The CPU time spent updating the frame.
+    pub cpu: f32,
+    /// The GPU time spent by each pass, filled in once the queries completed.
+    pub passes: Vec<(&'static str, f32)>,
+}
+
+impl Frame {
+    /// The GPU time spent by all the passes.
+    pub fn gpu(&self) -> f32 {
+        self.passes.iter().map(|(_, time)| time).sum()
+    }
+}
+
+/// Measures the CPU frame times and the GPU time of the render passes,
+/// through `GL_TIME_ELAPSED` queries collected asynchronously to avoid stalling.
+#[derive(Debug, Default)]
+pub struct Timings {
+    /// Show the timings over the canvas in live mode.
+    pub overlay: bool,
+
+    frames: VecDeque<Frame>,
+    /// The number of the first frame of the history.
+    first: u64,
+    started: Option<time::Instant>,
+
+    /// The queries in flight, oldest first, with their frame number and pass.
+    pending: VecDeque<(u64, &'static str, AllocGuard<glow::Query>)>,
+    /// The completed queries, ready to be reused.
+    pool: Vec<AllocGuard<glow::Query>>,
+}
+
+impl Timings {
+    /// The number of frames kept in the history.
+    pub const HISTORY: usize = 240;
+
+    /// Start timing a new frame, collecting the completed queries of the previous ones.
+    pub fn begin_frame(&mut self, gl: &Rc<glow::Context>) {
+        let now = time::Instant::now();
+        let interval = self
+            .started
+            .replace(now)
+            .map(|started| (now - started).as_secs_f32() * 1000.0)
+            .unwrap_or_default();
+
+        unsafe { self.collect(gl) };
+
+        self.frames.push_back(Frame {
+            interval,
+            ..Default::default()
+        });
+        while self.frames.len() > Self::HISTORY {
+            self.frames.pop_front();
+            self.first += 1;
+        }
+    }
+
+    /// Finish timing the CPU side of the current frame.
+    pub fn end_frame(&mut self) {
+        if let (Some(started), Some(frame)) = (self.started, self.frames.back_mut()) {
+            frame.cpu = started.elapsed().as_secs_f32() * 1000.0;
+        }
+    }
+
+    /// Measure the GPU time of the commands issued by `commands`, as part of `pass`.
+    pub(super) unsafe fn measure<T>(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        pass: &'static str,
+        commands: impl FnOnce() -> T,
+    ) -> Result<T, Error> {
+        let query = match self.pool.pop() {
+            Some(query) => query,
+            None => guard!(gl, gl.create_query().map_err(Error::Gl)?, move |query| gl
+                .delete_query(query)),
+        };
+
+        gl.begin_query(glow::TIME_ELAPSED, *query);
+        let output = commands();
+        gl.end_query(glow::TIME_ELAPSED);
+
+        let number = self.first + self.frames.len().saturating_sub(1) as u64;
+        self.pending.push_back((number, pass, query));
+
+        Ok(output)
+    }
+
+    /// Add the GPU time `elapsed` in milliseconds to the `pass` of the current frame,
+    /// for the passes measured elsewhere.
+    pub(super) fn record(&mut self, pass: &'static str, elapsed: f32) {
+        if let Some(frame) = self.frames.back_mut() {
+            match frame.passes.iter_mut().find(|(name, _)| *name == pass) {
+                Some((_, time)) => *time += elapsed,
+                None => frame.passes.push((pass, elapsed)),
+            }
+        }
+    }
+
+    /// Read back the results of the completed queries, without waiting for the others.
+    unsafe fn collect(&mut self, gl: &Rc<glow::Context>) {
+        while let Some((_, _, query)) = self.pending.front() {
+            if gl.get_query_parameter_u32(**query, glow::QUERY_RESULT_AVAILABLE) == 0 {
+                break;
+            }
+
+            let Some((number, pass, query)) = self.pending.pop_front() else {
+                break;
+            };
+            let elapsed = gl.get_query_parameter_u32(*query, glow::QUERY_RESULT) as f32 / 1e6;
+
+            if let Some(frame) = number
+                .checked_sub(self.first)
+                .and_then(|index| self.frames.get_mut(index as usize))
+            {
+                match frame.passes.iter_mut().find(|(name, _)| *name == pass) {
+                    Some((_, time)) => *time += elapsed,
+                    None => frame.passes.push((pass, elapsed)),
+                }
+            }
+
+            self.pool.push(query);
+        }
+    }
+
+    /// The history of the completed frames, oldest first, excluding the current one and those with pending queries.
+    pub fn frames(&self) -> vec_deque::Iter<'_, Frame> {
+        let completed = self
+            .pending
+            .front()
+            .map(|(number, ..)| number.saturating_sub(self.first) as usize)
+            .unwrap_or(usize::MAX)
+            .min(self.frames.len().saturating_sub(1));
+
+        self.frames.range(..completed)
+    }
+
+    /// The average of the completed frames of the last second.
+    pub fn average(&self) -> Option<Frame> {
+        let mut elapsed = 0.0;
+
+        let frames = self
+            .frames()
+            .rev()
+            .take_while(|frame| {
+                elapsed += frame.interval;
+                elapsed <= 1000.0
+            })
+            .collect::<Vec<_>>();
+
+        if frames.is_empty() {
+            return None;
+        }
+
+        let count = frames.len() as f32;
+        let mut average = Frame::default();
+        for frame in frames {
+            average.interval += frame.interval / count;
+            average.cpu += frame.cpu / count;
+
+            for (pass, time) in &frame.passes {
+                match average.passes.iter_mut().find(|(name, _)| name == pass) {
+                    Some((_, average)) => *average += time / count,
+                    None => average.passes.push((pass, time / count)),
+                }
+            }
+        }
+
+        Some(average)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+
+    /// The timings of frames spaced by `interval`, with their CPU time the number of the frame
+    /// and a single pass of twice that.
+    fn timings(count: usize, interval: f32) -> Timings {
+        Timings {
+            frames: (0..count)
+                .map(|number| Frame {
+                    interval,
+                    cpu: number as f32,
+                    passes: vec![("Shader", number as f32 * 2.0)],
+                })
+                .collect(),
+            first: 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let mut timings = timings(5, 16.0);
+        // The current frame is never complete
+        assert_eq!(timings.frames().len(), 4);
+
+        // Nor are the ones with queries in flight
+        let query = glow::NativeQuery(NonZeroU32::MIN);
+        timings
+            .pending
+            .push_back((12, "Shader", scopeguard::guard(query, Box::new(|_| ()))));
+        assert_eq!(
+            timings.frames().map(|frame| frame.cpu).collect::<Vec<_>>(),
+            [0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn average() {
+        assert!(Timings::default().average().is_none());
+
+        // The four completed frames of the last second, out of the six completed ones
+        let average = timings(7, 250.0).average().expect("No completed frames");
+        assert_eq!(average.interval, 250.0);
+        assert_eq!(average.cpu, (2.0 + 3.0 + 4.0 + 5.0) / 4.0);
+        assert_eq!(average.passes, [("Shader", 7.0)]);
+        assert_eq!(average.gpu(), 7.0);
+    }
+}
@@ -1,12 +1,19 @@
-use std::{rc::Rc, time};
+use std::{collections::BTreeMap, rc::Rc, time};
 
-use eframe::{
-    egui,
-    glow::{self, HasContext},
-};
-use strum::{AsRefStr, EnumIter};
+use glow::HasContext;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
-#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr)]
+/// Audio-derived values sent to the shader alongside the audio texture.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Levels {
+    pub bass: f32,
+    pub mid: f32,
+    pub treble: f32,
+    pub beat: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, EnumIter, AsRefStr, Serialize, Deserialize)]
 pub enum UniformStyle {
     #[default]
     #[strum(serialize = "Classic (u_<name>)")]
@@ -35,49 +42,175 @@ impl UniformStyle {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Uniforms {
     pub style: UniformStyle,
     epoch: Option<time::Instant>,
 
     time: f32,
-    mouse: egui::Vec2,
-    resolution: egui::Vec2,
+    mouse: [f32; 2],
+    resolution: [f32; 2],
+
+    levels: Option<Levels>,
+    navigation: Option<(f32, [f32; 2], [f32; 2], f32)>,
+    input: Option<([f32; 2], f32)>,
+    textures: BTreeMap<&'static str, (glow::Texture, [f32; 2])>,
+
+    pub parameters: BTreeMap<String, f32>,
 }
 
 impl Uniforms {
-    pub fn update(&mut self, viewport: egui::Rect, mouse: Option<egui::Pos2>) {
+    const BUILTINS: [&'static str; 13] = [
+        "time",
+        "mouse",
+        "resolution",
+        "bass",
+        "mid",
+        "treble",
+        "beat",
+        "zoom",
+        "offset",
+        "offsetLow",
+        "rotation",
+        "ndiResolution",
+        "ndiAge",
+    ];
+
+    /// Advance the `time`, and set the `resolution` and the position of the `mouse`,
+    /// in pixels from the bottom-left corner, or the center if `None`.
+    pub fn update(&mut self, resolution: [f32; 2], mouse: Option<[f32; 2]>) {
         self.time = self
             .epoch
             .get_or_insert_with(time::Instant::now)
             .elapsed()
             .as_secs_f32();
 
-        if let Some(mouse) = mouse {
-            self.mouse = egui::vec2(mouse.x - viewport.left(), viewport.bottom() - mouse.y);
-        } else {
-            self.mouse = viewport.center() - viewport.min;
+        self.mouse = mouse.unwrap_or([resolution[0] / 2.0, resolution[1] / 2.0]);
+        self.resolution = resolution;
+    }
+
+    /// Keep track of the user-defined `float` uniforms declared by the shader.
+    pub fn declare(&mut self, names: &[String]) {
+        let names = names
+            .iter()
+            .filter(|name| {
+                !UniformStyle::iter().any(|style| {
+                    Self::BUILTINS
+                        .iter()
+                        .any(|builtin| style.format(builtin) == **name)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.parameters.retain(|name, _| names.contains(&name));
+        for name in names {
+            self.parameters.entry(name.clone()).or_default();
         }
+    }
+
+    /// Set or clear the audio-derived uniform values.
+    pub fn set_levels(&mut self, levels: Option<Levels>) {
+        self.levels = levels;
+    }
+
+    /// Set or clear the navigation `zoom`, `offset`, `offsetLow` and `rotation` uniform values.
+    pub fn set_navigation(&mut self, navigation: Option<(f32, [f32; 2], [f32; 2], f32)>) {
+        self.navigation = navigation;
+    }
+
+    /// Set or clear the resolution and age, in seconds, of the received NDI® frame.
+    pub fn set_input(&mut self, input: Option<([f32; 2], f32)>) {
+        self.input = input;
+    }
+
+    /// Bind or unbind a `sampler2D` uniform to a texture of the specified size.
+    pub fn set_texture(&mut self, name: &'static str, texture: Option<(glow::Texture, [f32; 2])>) {
+        match texture {
+            Some(texture) => self.textures.insert(name, texture),
+            None => self.textures.remove(name),
+        };
+    }
 
-        self.resolution = viewport.size();
+    pub fn set_parameter(&mut self, name: &str, value: f32) {
+        if let Some(parameter) = self.parameters.get_mut(name) {
+            *parameter = value;
+        }
     }
 
-    pub fn to_iter(&self) -> impl Iterator<Item = (String, Vec<f32>)> {
+    pub fn builtins(&self) -> impl Iterator<Item = (String, Vec<f32>)> + '_ {
         [
             (self.style.format("time"), vec![self.time]),
-            (self.style.format("mouse"), vec![self.mouse.x, self.mouse.y]),
-            (
-                self.style.format("resolution"),
-                vec![self.resolution.x, self.resolution.y],
-            ),
+            (self.style.format("mouse"), self.mouse.to_vec()),
+            (self.style.format("resolution"), self.resolution.to_vec()),
         ]
         .into_iter()
+        .chain(self.levels.iter().flat_map(|levels| {
+            [
+                (self.style.format("bass"), vec![levels.bass]),
+                (self.style.format("mid"), vec![levels.mid]),
+                (self.style.format("treble"), vec![levels.treble]),
+                (
+                    self.style.format("beat"),
+                    vec![if levels.beat { 1.0 } else { 0.0 }],
+                ),
+            ]
+        }))
+        .chain(
+            self.navigation
+                .iter()
+                .flat_map(|(zoom, offset, low, rotation)| {
+                    [
+                        (self.style.format("zoom"), vec![*zoom]),
+                        (self.style.format("offset"), offset.to_vec()),
+                        (self.style.format("offsetLow"), low.to_vec()),
+                        (self.style.format("rotation"), vec![*rotation]),
+                    ]
+                }),
+        )
+        .chain(self.input.iter().flat_map(|(resolution, age)| {
+            [
+                (self.style.format("ndiResolution"), resolution.to_vec()),
+                (self.style.format("ndiAge"), vec![*age]),
+            ]
+        }))
+    }
+
+    pub fn to_iter(&self) -> impl Iterator<Item = (String, Vec<f32>)> + '_ {
+        self.builtins().chain(
+            self.parameters
+                .iter()
+                .map(|(name, value)| (name.clone(), vec![*value])),
+        )
+    }
+
+    /// The bound textures, with their formatted uniform name and size.
+    pub fn textures(&self) -> impl Iterator<Item = (String, glow::Texture, [f32; 2])> + '_ {
+        self.textures
+            .iter()
+            .map(|(name, (texture, size))| (self.style.format(name), *texture, *size))
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
     }
 
     pub fn reset_time(&mut self) {
         self.epoch = None;
     }
 
+    /// Move the `time` uniform to the specified value, in seconds.
+    pub fn seek(&mut self, time: f32) {
+        let now = time::Instant::now();
+
+        self.epoch = now.checked_sub(time::Duration::from_secs_f32(time.max(0.0)));
+        self.time = now.duration_since(self.epoch.unwrap_or(now)).as_secs_f32();
+    }
+
+    /// Set the uniforms and bind the textures of the `program`, in use.
+    ///
+    /// # Safety
+    ///
+    /// The `gl` context must be current on this thread.
     pub unsafe fn apply(&self, gl: &Rc<glow::Context>, program: glow::Program) {
         for (name, value) in self.to_iter() {
             let location = gl.get_uniform_location(program, &name);
@@ -90,5 +223,14 @@ impl Uniforms {
                 _ => panic!("Mis-sized uniform value"),
             }
         }
+
+        for (unit, (name, texture, _)) in self.textures().enumerate() {
+            let location = gl.get_uniform_location(program, &name);
+
+            gl.active_texture(glow::TEXTURE0 + unit as u32);
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+            gl.uniform_1_i32(location.as_ref(), unit as i32);
+        }
+        gl.active_texture(glow::TEXTURE0);
     }
 }
@@ -0,0 +1,173 @@
+use std::{path::PathBuf, rc::Rc};
+
+use eframe::{egui, glow};
+
+use super::{Display, Format, Pool, Shader, Uniforms};
+use crate::{
+    canvas::{Canvas, View},
+    AllocGuard, Error,
+};
+
+/// Sets the uniforms of a [`ShaderView`] before each render.
+type Provider = Box<dyn FnMut(&mut Uniforms)>;
+
+/// A live preview of a shader for any egui application using the `glow` renderer,
+/// added to an interface with `ui.add(&mut view)` and owning its OpenGL resources.
+pub struct ShaderView {
+    pub format: Format,
+    pub display: Display,
+
+    gl: Rc<glow::Context>,
+    shader: Shader,
+    uniforms: Uniforms,
+    provider: Option<Provider>,
+    size: Option<egui::Vec2>,
+    error: Option<Error>,
+}
+
+impl std::fmt::Debug for ShaderView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShaderView")
+            .field("format", &self.format)
+            .field("display", &self.display)
+            .field("shader", &self.shader)
+            .field("uniforms", &self.uniforms)
+            .field("size", &self.size)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShaderView {
+    /// Preview the `shader` with the `gl` context of the application, as given by `eframe::Frame::gl`.
+    pub fn new(gl: Rc<glow::Context>, shader: Shader) -> Self {
+        Self {
+            format: Format::default(),
+            display: Display::default(),
+            gl,
+            shader,
+            uniforms: Uniforms::default(),
+            provider: None,
+            size: None,
+            error: None,
+        }
+    }
+
+    /// Preview the shader at `path`, recompiled whenever the file changes.
+    pub fn from_path(gl: Rc<glow::Context>, path: impl Into<PathBuf>) -> Self {
+        Self::new(gl, Shader::new(path.into()))
+    }
+
+    /// Preview the shader `source`.
+    pub fn from_source(gl: Rc<glow::Context>, source: impl Into<String>) -> Self {
+        Self::new(gl, Shader::from_source(source.into()))
+    }
+
+    /// Let `provider` set the uniforms before each render, such as the shader parameters.
+    pub fn uniforms(mut self, provider: impl FnMut(&mut Uniforms) + 'static) -> Self {
+        self.provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Take `size` in the interface, in points, rather than all the available space.
+    pub fn desired_size(mut self, size: egui::Vec2) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// The error that occured while compiling or rendering the shader, if any.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Render the shader to a texture of `size`, mapped for display.
+    unsafe fn render(
+        &mut self,
+        size: egui::Vec2,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        if self.shader.inner().is_none() || size.x < 1.0 || size.y < 1.0 {
+            return Ok(None);
+        }
+
+        let target =
+            self.shader
+                .render_to_texture(&self.gl, &self.uniforms, size.into(), self.format)?;
+        let (canvas, highlight) = (self.display.canvas, self.display.highlight);
+
+        Ok(Some(
+            self.display
+                .render(&self.gl, *target, size, canvas, highlight)?
+                .unwrap_or(target),
+        ))
+    }
+}
+
+impl egui::Widget for &mut ShaderView {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let size = self.size.unwrap_or_else(|| ui.available_size_before_wrap());
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+
+        let ppp = painter.ctx().pixels_per_point();
+        let viewport = egui::Rect {
+            min: painter.round_pos_to_pixels(painter.clip_rect().min),
+            max: painter.round_pos_to_pixels(painter.clip_rect().max),
+        } * ppp;
+
+        match self.shader.rebuild(&self.gl) {
+            Ok(true) => {
+                self.uniforms.declare(self.shader.parameters());
+                self.error = None;
+            }
+            Ok(false) => (),
+            Err(err) => {
+                tracing::warn!("Unable to compile shader view: {err}");
+                self.error = Some(err);
+            }
+        }
+
+        // The hovered position, in pixels from the bottom-left corner
+        let mouse = response.hover_pos().map(|pos| {
+            [
+                pos.x * ppp - viewport.left(),
+                viewport.bottom() - pos.y * ppp,
+            ]
+        });
+        self.uniforms.update(viewport.size().into(), mouse);
+        if let Some(provider) = &mut self.provider {
+            provider(&mut self.uniforms);
+        }
+
+        let texture = unsafe {
+            Pool::trim_once(&self.gl, ui.ctx().frame_nr());
+
+            self.render(viewport.size())
+        };
+        let texture = match texture {
+            Ok(texture) => texture,
+            Err(err) => {
+                tracing::warn!("Unable to render shader view: {err}");
+                self.error = Some(err);
+
+                None
+            }
+        };
+
+        // Keep the preview live
+        ui.ctx().request_repaint();
+
+        let overlay = painter.clone();
+        Canvas::new(texture, painter, response.clone(), View::default()).paint();
+
+        if let Some(err) = &self.error {
+            overlay.text(
+                overlay.clip_rect().center(),
+                egui::Align2::CENTER_CENTER,
+                err.to_string(),
+                egui::FontId::monospace(10.0),
+                ui.visuals().error_fg_color,
+            );
+        }
+
+        response
+    }
+}
@@ -0,0 +1,511 @@
+use std::{
+    collections::HashMap,
+    ffi::{self, CStr, CString},
+    num::NonZeroU32,
+    rc::Rc,
+    sync::mpsc,
+    thread,
+};
+
+use eframe::{
+    egui,
+    glow::{self, HasContext},
+};
+use glutin::{
+    config::{Config, ConfigSurfaceTypes, ConfigTemplateBuilder},
+    context::{
+        AsRawContext, ContextApi, ContextAttributesBuilder, NotCurrentContext, RawContext, Version,
+    },
+    display::{Display, DisplayApiPreference},
+    prelude::*,
+    surface::{PbufferSurface, SurfaceAttributesBuilder},
+};
+use raw_window_handle::RawDisplayHandle;
+
+use super::{target, Format, Pool, Shader, Uniforms};
+use crate::{guard, AllocGuard, Error};
+
+/// The display connection of the application, used to create the context of the worker.
+#[derive(Debug, Clone, Copy)]
+pub struct Connection(RawDisplayHandle);
+
+impl From<RawDisplayHandle> for Connection {
+    fn from(handle: RawDisplayHandle) -> Self {
+        Self(handle)
+    }
+}
+
+/// The context current on the interface thread, which the worker shares its objects with.
+struct Shared(RawContext);
+
+impl AsRawContext for Shared {
+    fn raw_context(&self) -> RawContext {
+        self.0
+    }
+}
+
+/// A fence of the shared contexts, signaled once the commands issued before it completed.
+struct Fence(glow::Fence);
+
+// The sync objects are shared by the contexts, and can be waited on from any of their threads.
+unsafe impl Send for Fence {}
+
+impl Fence {
+    /// Insert a fence after the commands issued so far, flushed for the other context to see it.
+    unsafe fn new(gl: &glow::Context) -> Result<Self, Error> {
+        let fence = gl
+            .fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0)
+            .map_err(Error::Gl)?;
+        gl.flush();
+
+        Ok(Self(fence))
+    }
+
+    /// Make the next commands of this context wait for the fence without blocking, and delete it.
+    unsafe fn wait(self, gl: &glow::Context) {
+        gl.wait_sync(self.0, 0, glow::TIMEOUT_IGNORED);
+        gl.delete_sync(self.0);
+    }
+}
+
+/// A render requested from the [`Worker`], once the inputs uploaded before the `fence`,
+/// such as the audio and NDI® textures, are ready.
+struct Job {
+    source: Option<String>,
+    uniforms: Uniforms,
+    size: egui::Vec2,
+    format: Format,
+    fence: Fence,
+}
+
+/// A request to the rendering thread.
+enum Request {
+    Render(Job),
+    /// Reuse the texture of a frame once the interface is done reading it, before the fence.
+    Return(glow::Texture, Fence),
+}
+
+/// A frame rendered by the [`Worker`] in a texture of the shared contexts, complete once the `fence` is.
+struct Frame {
+    texture: glow::Texture,
+    size: egui::Vec2,
+    /// The GPU time spent rendering it, in milliseconds.
+    elapsed: f32,
+    fence: Fence,
+}
+
+/// An event of the rendering thread.
+enum Event {
+    /// The shader was compiled, declaring these parameters.
+    Compiled(Vec<String>),
+    /// The requested render completed, if there was anything to render.
+    Rendered(Option<Frame>),
+    /// The requested render failed.
+    Failed(Error),
+}
+
+/// Compiles and renders the shader on a dedicated thread, with a context sharing its objects
+/// with the interface one, so that slow shaders don't block the interface.
+///
+/// The frames are handed over as textures along with fences, and the inputs bound to the shader
+/// are read from the textures of the interface.
+pub struct Worker {
+    requests: mpsc::Sender<Request>,
+    events: mpsc::Receiver<Event>,
+    /// Whether a render was requested and didn't complete yet.
+    pending: bool,
+    /// The source last sent to the rendering thread.
+    source: String,
+
+    /// The latest frame, with its size and the framebuffer reading it on the interface context.
+    latest: Option<(glow::Texture, egui::Vec2, AllocGuard<glow::Framebuffer>)>,
+    /// The GPU time spent rendering the latest frame, until it's taken for the timings.
+    elapsed: Option<f32>,
+}
+
+impl std::fmt::Debug for Worker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Worker")
+            .field("pending", &self.pending)
+            .field("latest", &self.latest.as_ref().map(|(_, size, _)| size))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Worker {
+    /// Create a context on the `connection` sharing with the one current on this thread,
+    /// and start the rendering thread.
+    pub fn new(connection: Connection) -> Result<Self, Error> {
+        let (display, config, context) = unsafe { Self::context(connection)? };
+
+        let (requests, receiver) = mpsc::channel();
+        let (sender, events) = mpsc::channel();
+        let (ready, started) = mpsc::channel();
+
+        // The thread is detached, and stops once the requests are hung up
+        thread::Builder::new()
+            .name("render".into())
+            .spawn(move || {
+                let context = unsafe { Self::make_current(&display, &config, context) };
+                let (gl, _context) = match context {
+                    Ok(context) => {
+                        let _ = ready.send(Ok(()));
+
+                        context
+                    }
+                    Err(err) => {
+                        let _ = ready.send(Err(err));
+
+                        return;
+                    }
+                };
+
+                Self::run(Rc::new(gl), receiver, sender)
+            })?;
+
+        started
+            .recv()
+            .expect("Rendering thread panicked while starting")?;
+
+        tracing::info!("Started rendering on a dedicated thread");
+
+        Ok(Self {
+            requests,
+            events,
+            pending: false,
+            source: String::new(),
+            latest: None,
+            elapsed: None,
+        })
+    }
+
+    /// Create an OpenGL 3.3 context on the `connection` with the platform of eframe,
+    /// sharing its objects with the context current on this thread.
+    unsafe fn context(
+        connection: Connection,
+    ) -> Result<(Display, Config, NotCurrentContext), Error> {
+        // The platform eframe ended up with depends on the drivers, so look for the one it made current
+        #[cfg(target_os = "windows")]
+        let preferences: [fn() -> DisplayApiPreference; 2] = [
+            || DisplayApiPreference::Wgl(None),
+            || DisplayApiPreference::Egl,
+        ];
+        #[cfg(target_os = "macos")]
+        let preferences: [fn() -> DisplayApiPreference; 1] = [|| DisplayApiPreference::Cgl];
+        // The Xlib errors are already handled by winit for the whole application
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let preferences: [fn() -> DisplayApiPreference; 2] = [
+            || DisplayApiPreference::Glx(Box::new(|_| ())),
+            || DisplayApiPreference::Egl,
+        ];
+
+        let mut shared = None;
+        for preference in preferences {
+            // The platform may not be available at all, such as GLX on Wayland
+            let Ok(display) = Display::new(connection.0, preference()) else {
+                continue;
+            };
+
+            if let Some(current) = Self::current(&display)? {
+                shared = Some((display, current));
+
+                break;
+            }
+        }
+        let (display, current) =
+            shared.ok_or_else(|| Error::Gl("No context is current on this thread".into()))?;
+
+        let config = display
+            .find_configs(
+                ConfigTemplateBuilder::new()
+                    .with_surface_type(ConfigSurfaceTypes::PBUFFER)
+                    .build(),
+            )?
+            .next()
+            .ok_or_else(|| {
+                Error::Gl("No suitable configuration for the rendering context".into())
+            })?;
+
+        let context = display.create_context(
+            &config,
+            &ContextAttributesBuilder::new()
+                .with_sharing(&Shared(current))
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+                .build(None),
+        )?;
+
+        Ok((display, config, context))
+    }
+
+    /// The context of the platform of the `display` current on this thread, if any.
+    unsafe fn current(display: &Display) -> Result<Option<RawContext>, Error> {
+        let (name, raw): (&[u8], fn(*const ffi::c_void) -> RawContext) = match display {
+            #[cfg(not(target_os = "macos"))]
+            Display::Egl(_) => (b"eglGetCurrentContext\0", RawContext::Egl),
+            #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+            Display::Glx(_) => (b"glXGetCurrentContext\0", RawContext::Glx),
+            #[cfg(target_os = "windows")]
+            Display::Wgl(_) => (b"wglGetCurrentContext\0", RawContext::Wgl),
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(Error::Gl(format!(
+                    "Sharing the rendering context isn't supported with {}",
+                    display.version_string()
+                )))
+            }
+        };
+
+        let current = display
+            .get_proc_address(CStr::from_bytes_with_nul(name).expect("Invalid function name"));
+        if current.is_null() {
+            return Ok(None);
+        }
+
+        let current: unsafe extern "system" fn() -> *const ffi::c_void =
+            std::mem::transmute(current);
+        let current = current();
+
+        Ok((!current.is_null()).then(|| raw(current)))
+    }
+
+    /// Make the `context` current on this thread, with a pbuffer surface.
+    unsafe fn make_current(
+        display: &Display,
+        config: &Config,
+        context: NotCurrentContext,
+    ) -> Result<
+        (
+            glow::Context,
+            (
+                glutin::context::PossiblyCurrentContext,
+                glutin::surface::Surface<PbufferSurface>,
+            ),
+        ),
+        Error,
+    > {
+        let surface = display.create_pbuffer_surface(
+            config,
+            &SurfaceAttributesBuilder::<PbufferSurface>::new()
+                .build(NonZeroU32::MIN, NonZeroU32::MIN),
+        )?;
+        let context = context.make_current(&surface)?;
+
+        let gl = glow::Context::from_loader_function(|name| {
+            let name = CString::new(name).expect("Invalid OpenGL function name");
+
+            display.get_proc_address(&name)
+        });
+
+        Ok((gl, (context, surface)))
+    }
+
+    /// Collect the events of the rendering thread, returning the parameters of the shader once compiled,
+    /// or the error which occured while compiling or rendering it.
+    pub fn poll(&mut self, gl: &Rc<glow::Context>) -> Result<Option<Vec<String>>, Error> {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Event::Compiled(parameters) => return Ok(Some(parameters)),
+                Event::Rendered(frame) => {
+                    self.pending = false;
+
+                    if let Some(frame) = frame {
+                        unsafe { self.receive(gl, frame)? };
+                    }
+                }
+                Event::Failed(err) => {
+                    self.pending = false;
+
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Make the `frame` the latest one, returning the previous one to the rendering thread.
+    unsafe fn receive(&mut self, gl: &Rc<glow::Context>, frame: Frame) -> Result<(), Error> {
+        frame.fence.wait(gl);
+        self.elapsed = Some(frame.elapsed);
+
+        // The framebuffers aren't shared by the contexts
+        let framebuffer = guard!(
+            gl,
+            gl.create_framebuffer().map_err(Error::Gl)?,
+            move |framebuffer| gl.delete_framebuffer(framebuffer)
+        );
+
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(*framebuffer));
+        gl.framebuffer_texture_2d(
+            glow::READ_FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(frame.texture),
+            0,
+        );
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+
+        if let Some((texture, ..)) = self
+            .latest
+            .replace((frame.texture, frame.size, framebuffer))
+        {
+            // The thread may have stopped, along with its textures
+            let _ = self
+                .requests
+                .send(Request::Return(texture, Fence::new(gl)?));
+        }
+
+        Ok(())
+    }
+
+    /// Request a render of the `shader` with the `uniforms` at `size` and `format` once the previous one completed,
+    /// returning a copy of the latest frame stretched to `size`, if any.
+    pub(super) unsafe fn render(
+        &mut self,
+        gl: &Rc<glow::Context>,
+        shader: &Shader,
+        uniforms: &Uniforms,
+        size: egui::Vec2,
+        format: Format,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        if !self.pending {
+            let source = (shader.source() != self.source).then(|| shader.source().to_string());
+            if let Some(source) = &source {
+                self.source.clone_from(source);
+            }
+
+            let job = Job {
+                source,
+                uniforms: uniforms.clone(),
+                size,
+                format,
+                fence: Fence::new(gl)?,
+            };
+
+            if self.requests.send(Request::Render(job)).is_err() {
+                return Err(Error::Gl("The rendering thread stopped".into()));
+            }
+            self.pending = true;
+        }
+
+        self.latest(gl, size, format)
+    }
+
+    /// Take the GPU time spent rendering the latest frame on the rendering thread, in milliseconds,
+    /// if it wasn't taken yet.
+    pub(super) fn elapsed(&mut self) -> Option<f32> {
+        self.elapsed.take()
+    }
+
+    /// A copy of the latest frame stretched to `size` in `format`, if any.
+    pub(super) unsafe fn latest(
+        &self,
+        gl: &Rc<glow::Context>,
+        size: egui::Vec2,
+        format: Format,
+    ) -> Result<Option<AllocGuard<glow::Texture>>, Error> {
+        let Some((_, current, framebuffer)) = &self.latest else {
+            return Ok(None);
+        };
+
+        target::render_to_texture(gl, size.into(), format, || {
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(**framebuffer));
+            gl.blit_framebuffer(
+                0,
+                0,
+                current.x as i32,
+                current.y as i32,
+                0,
+                0,
+                size.x as i32,
+                size.y as i32,
+                glow::COLOR_BUFFER_BIT,
+                glow::NEAREST,
+            );
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+        })
+        .map(Some)
+    }
+
+    /// Render the jobs as they come, until the requests are hung up.
+    fn run(gl: Rc<glow::Context>, requests: mpsc::Receiver<Request>, events: mpsc::Sender<Event>) {
+        let mut shader = None;
+        // The textures of the frames handed over to the interface, released once returned
+        let mut frames = HashMap::new();
+
+        for request in requests {
+            let job = match request {
+                Request::Render(job) => job,
+                Request::Return(texture, fence) => {
+                    unsafe { fence.wait(&gl) };
+                    frames.remove(&texture);
+
+                    continue;
+                }
+            };
+
+            let event = match unsafe { Self::render_job(&gl, &mut shader, job, &events) } {
+                Ok(Some((texture, frame))) => {
+                    frames.insert(*texture, texture);
+
+                    Event::Rendered(Some(frame))
+                }
+                Ok(None) => Event::Rendered(None),
+                Err(err) => Event::Failed(err),
+            };
+
+            unsafe { Pool::trim(&gl) };
+
+            if events.send(event).is_err() {
+                break;
+            }
+        }
+
+        drop(frames);
+        unsafe { Pool::clear(&gl) };
+    }
+
+    /// Compile the source of the `job` if it changed, and render it.
+    unsafe fn render_job(
+        gl: &Rc<glow::Context>,
+        shader: &mut Option<Shader>,
+        job: Job,
+        events: &mpsc::Sender<Event>,
+    ) -> Result<Option<(AllocGuard<glow::Texture>, Frame)>, Error> {
+        job.fence.wait(gl);
+
+        // Keep rendering the previous shader until the new source compiles
+        if let Some(source) = job.source {
+            let mut rebuilt = Shader::from_source(source);
+            rebuilt.rebuild(gl)?;
+
+            let shader = shader.insert(rebuilt);
+            let _ = events.send(Event::Compiled(shader.parameters().to_vec()));
+        }
+
+        let Some(shader) = shader.as_ref().filter(|shader| shader.inner().is_some()) else {
+            return Ok(None);
+        };
+        if job.size.x < 1.0 || job.size.y < 1.0 {
+            return Ok(None);
+        }
+
+        let query = guard!(gl, gl.create_query().map_err(Error::Gl)?, move |query| gl
+            .delete_query(query));
+
+        gl.begin_query(glow::TIME_ELAPSED, *query);
+        let texture = shader.render_to_texture(gl, &job.uniforms, job.size.into(), job.format);
+        gl.end_query(glow::TIME_ELAPSED);
+        let texture = texture?;
+
+        // Waiting for the query only blocks this thread, which waits for the render anyway
+        let frame = Frame {
+            texture: *texture,
+            size: job.size,
+            elapsed: gl.get_query_parameter_u32(*query, glow::QUERY_RESULT) as f32 / 1e6,
+            fence: Fence::new(gl)?,
+        };
+
+        Ok(Some((texture, frame)))
+    }
+}
@@ -0,0 +1,49 @@
+//! The helpers shared by the integration tests.
+
+use std::{ffi::CString, rc::Rc};
+
+use glutin::{
+    api::egl::{context::PossiblyCurrentContext, device::Device, display::Display},
+    config::{Api, ConfigSurfaceTypes, ConfigTemplateBuilder},
+    context::{ContextApi, ContextAttributesBuilder, Version},
+    prelude::*,
+};
+
+/// A shader rendering a constant color, exactly representable in 8 bits.
+pub const CONSTANT: &str = r#"
+    #version 330 core
+
+    out vec4 color;
+
+    void main() {
+        color = vec4(1.0, 0.2, 0.0, 1.0);
+    }
+"#;
+
+/// An OpenGL 3.3 context current on this thread without any surface, on the first EGL device.
+pub fn context() -> Option<(Rc<glow::Context>, PossiblyCurrentContext)> {
+    let device = Device::query_devices().ok()?.next()?;
+    let display = unsafe { Display::with_device(&device, None) }.ok()?;
+
+    let template = ConfigTemplateBuilder::new()
+        .with_api(Api::OPENGL)
+        .with_surface_type(ConfigSurfaceTypes::empty())
+        .build();
+    let config = unsafe { display.find_configs(template) }.ok()?.next()?;
+
+    let attributes = ContextAttributesBuilder::new()
+        .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+        .build(None);
+    let context = unsafe { display.create_context(&config, &attributes) }
+        .ok()?
+        .make_current_surfaceless()
+        .ok()?;
+
+    let gl = unsafe {
+        glow::Context::from_loader_function(|name| {
+            display.get_proc_address(&CString::new(name).expect("Invalid OpenGL function name"))
+        })
+    };
+
+    Some((Rc::new(gl), context))
+}
@@ -0,0 +1,35 @@
+//! Render shaders through the [`Headless`] API on a surfaceless EGL context.
+
+use glou::{Format, Headless};
+
+mod common;
+
+#[test]
+fn constant() {
+    let Some((gl, _context)) = common::context() else {
+        eprintln!("Skipping the headless render, no EGL device is available");
+
+        return;
+    };
+
+    let mut headless =
+        Headless::from_source(gl, common::CONSTANT).expect("Unable to compile the shader");
+
+    let pixels = headless
+        .render_to_buffer(4, 3)
+        .expect("Unable to render the shader");
+    assert_eq!(pixels.len(), 4 * 3 * Format::Rgba8.bytes());
+    for pixel in pixels.chunks_exact(4) {
+        assert_eq!(pixel, [255, 51, 0, 255]);
+    }
+
+    headless.format = Format::Rgba32F;
+    let pixels = headless
+        .render_to_buffer(2, 2)
+        .expect("Unable to render the shader");
+    let pixels = pixels
+        .chunks_exact(4)
+        .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect::<Vec<_>>();
+    assert_eq!(pixels, [1.0, 0.2, 0.0, 1.0].repeat(4));
+}
@@ -0,0 +1,67 @@
+//! Reuse and trim the render targets of the [`Pool`], through the [`Headless`] renders.
+
+use glou::{Format, Headless, Pool};
+
+mod common;
+
+#[test]
+fn reuse() {
+    let Some((gl, _context)) = common::context() else {
+        eprintln!("Skipping the render target pool, no EGL device is available");
+
+        return;
+    };
+
+    let mut headless =
+        Headless::from_source(gl, common::CONSTANT).expect("Unable to compile the shader");
+
+    // The texture is released back to the pool as soon as it's dropped
+    let texture = *headless.render_to_texture(4, 4).expect("Unable to render");
+    assert!(Pool::framebuffer(texture).is_some());
+
+    let reused = headless.render_to_texture(4, 4).expect("Unable to render");
+    assert_eq!(*reused, texture);
+
+    // The targets in use aren't shared, nor are those of another size or format
+    let other = headless.render_to_texture(4, 4).expect("Unable to render");
+    assert_ne!(*other, texture);
+
+    let resized = headless.render_to_texture(8, 4).expect("Unable to render");
+    assert_ne!(*resized, texture);
+
+    headless.format = Format::Rgba16F;
+    let formatted = headless.render_to_texture(4, 4).expect("Unable to render");
+    assert_ne!(*formatted, texture);
+    assert_ne!(*formatted, *other);
+}
+
+#[test]
+fn trim() {
+    let Some((gl, _context)) = common::context() else {
+        eprintln!("Skipping the render target pool, no EGL device is available");
+
+        return;
+    };
+
+    let mut headless =
+        Headless::from_source(gl.clone(), common::CONSTANT).expect("Unable to compile the shader");
+
+    let kept = headless.render_to_texture(4, 4).expect("Unable to render");
+    let released = *headless.render_to_texture(8, 8).expect("Unable to render");
+
+    // The released targets are kept for a couple of frames, then deleted
+    unsafe { Pool::trim(&gl) };
+    assert!(Pool::framebuffer(released).is_some());
+
+    for _ in 0..2 {
+        unsafe { Pool::trim(&gl) };
+    }
+    assert!(Pool::framebuffer(released).is_none());
+    assert!(Pool::framebuffer(*kept).is_some());
+
+    // All the released targets are deleted before the context goes away
+    let texture = *kept;
+    drop(kept);
+    unsafe { Pool::clear(&gl) };
+    assert!(Pool::framebuffer(texture).is_none());
+}
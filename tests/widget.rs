@@ -0,0 +1,83 @@
+//! Show [`ShaderView`]s in an egui interface run without any window, on a surfaceless EGL context.
+#![cfg(feature = "gui")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use eframe::egui;
+use glou::{Error, ShaderView};
+
+mod common;
+
+/// Run a frame of an interface showing the `view`, returning its response.
+fn frame(ctx: &egui::Context, view: &mut ShaderView) -> egui::Response {
+    let mut response = None;
+
+    let _ = ctx.run(Default::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| response = Some(ui.add(&mut *view)));
+    });
+
+    response.expect("The view wasn't shown")
+}
+
+#[test]
+fn parameters() {
+    let Some((gl, _context)) = common::context() else {
+        eprintln!("Skipping the shader view, no EGL device is available");
+
+        return;
+    };
+
+    let source = r#"
+        #version 330 core
+
+        uniform float gain;
+        out vec4 color;
+
+        void main() {
+            color = vec4(gain);
+        }
+    "#;
+
+    // The provider sees the parameters declared by the shader, before each render
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut view = ShaderView::from_source(gl, source)
+        .desired_size(egui::vec2(64.0, 32.0))
+        .uniforms({
+            let seen = seen.clone();
+
+            move |uniforms| {
+                uniforms.set_parameter("gain", 0.5);
+                seen.borrow_mut()
+                    .push(uniforms.parameters.get("gain").copied());
+            }
+        });
+
+    let ctx = egui::Context::default();
+    for _ in 0..2 {
+        let response = frame(&ctx, &mut view);
+        assert_eq!(response.rect.size(), egui::vec2(64.0, 32.0));
+    }
+
+    assert!(view.error().is_none(), "{:?}", view.error());
+    assert_eq!(*seen.borrow(), [Some(0.5), Some(0.5)]);
+}
+
+#[test]
+fn erroneous() {
+    let Some((gl, _context)) = common::context() else {
+        eprintln!("Skipping the shader view, no EGL device is available");
+
+        return;
+    };
+
+    let source = common::CONSTANT.replace("color = ", "color = undeclared + ");
+    let mut view = ShaderView::from_source(gl, source);
+
+    frame(&egui::Context::default(), &mut view);
+
+    assert!(
+        matches!(view.error(), Some(Error::Compile(_))),
+        "{:?}",
+        view.error()
+    );
+}